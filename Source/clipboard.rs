@@ -0,0 +1,175 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `Clipboard` struct and associated types.
+//!
+//! A [`Clipboard`] is obtained from [`EventLoopWindowTarget::clipboard`] and is only usable on the
+//! thread running the event loop. Changes of the clipboard owner are reported through
+//! [`Event::ClipboardChanged`].
+//!
+//! [`EventLoopWindowTarget::clipboard`]: crate::event_loop::EventLoopWindowTarget::clipboard
+//! [`Event::ClipboardChanged`]: crate::event::Event::ClipboardChanged
+use url::Url;
+
+#[cfg(not(any(
+	target_os = "linux",
+	target_os = "dragonfly",
+	target_os = "freebsd",
+	target_os = "netbsd",
+	target_os = "openbsd"
+)))]
+pub(crate) use self::unsupported::PlatformClipboard;
+use crate::{
+	error::ExternalError,
+	icon::{BadIcon, RgbaIcon},
+};
+#[cfg(any(
+	target_os = "linux",
+	target_os = "dragonfly",
+	target_os = "freebsd",
+	target_os = "netbsd",
+	target_os = "openbsd"
+))]
+pub(crate) use crate::platform_impl::Clipboard as PlatformClipboard;
+
+/// The selection a [`Clipboard`] reads from and writes to.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ClipboardKind {
+	/// The clipboard used by copy and paste.
+	#[default]
+	Clipboard,
+	/// The selection holding the most recently selected text, usually pasted with a middle
+	/// click.
+	///
+	/// ## Platform-specific
+	///
+	/// - **Windows / macOS / iOS / Android:** Unsupported.
+	Primary,
+}
+
+/// A 32bpp RGBA image stored in or read from a [`Clipboard`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardImage {
+	pub(crate) inner:RgbaIcon,
+}
+
+impl ClipboardImage {
+	/// Creates a `ClipboardImage` from 32bpp RGBA data.
+	///
+	/// The length of `rgba` must be divisible by 4, and `width * height` must
+	/// equal `rgba.len() / 4`. Otherwise, this will return a `BadIcon` error.
+	pub fn from_rgba(rgba:Vec<u8>, width:u32, height:u32) -> Result<Self, BadIcon> {
+		Ok(Self { inner:RgbaIcon::from_rgba(rgba, width, height)? })
+	}
+
+	/// Returns the RGBA pixels of the image, row by row.
+	pub fn rgba(&self) -> &[u8] { &self.inner.rgba }
+
+	/// Returns the width of the image in pixels.
+	pub fn width(&self) -> u32 { self.inner.width }
+
+	/// Returns the height of the image in pixels.
+	pub fn height(&self) -> u32 { self.inner.height }
+
+	/// Consumes the image and returns its RGBA pixels, row by row.
+	pub fn into_rgba(self) -> Vec<u8> { self.inner.rgba }
+}
+
+/// A handle to one of the system clipboards.
+///
+/// Reading waits for the clipboard owner to answer, which may be another application, while
+/// still processing events of the event loop.
+///
+/// ## Platform-specific
+///
+/// - **Windows / macOS / iOS / Android:** Unsupported, writing returns
+///   [`ExternalError::NotSupported`] and reading returns nothing.
+pub struct Clipboard {
+	pub(crate) inner:PlatformClipboard,
+	pub(crate) kind:ClipboardKind,
+}
+
+impl Clipboard {
+	/// Returns the selection this clipboard accesses.
+	pub fn kind(&self) -> ClipboardKind { self.kind }
+
+	/// Replaces the content of the clipboard with UTF-8 text.
+	pub fn set_text(&self, text:&str) -> Result<(), ExternalError> { self.inner.set_text(text) }
+
+	/// Returns the content of the clipboard as UTF-8 text, if it can be converted to text.
+	pub fn text(&self) -> Option<String> { self.inner.text() }
+
+	/// Replaces the content of the clipboard with an image.
+	pub fn set_image(&self, image:&ClipboardImage) -> Result<(), ExternalError> {
+		self.inner.set_image(image)
+	}
+
+	/// Returns the content of the clipboard as an image, if it can be converted to one.
+	pub fn image(&self) -> Option<ClipboardImage> { self.inner.image() }
+
+	/// Replaces the content of the clipboard with a list of URIs, usually the `file://` URIs of
+	/// copied files.
+	pub fn set_uris(&self, uris:&[Url]) -> Result<(), ExternalError> { self.inner.set_uris(uris) }
+
+	/// Returns the URIs held by the clipboard. Entries that aren't valid URIs are skipped.
+	pub fn uris(&self) -> Vec<Url> { self.inner.uris() }
+
+	/// Replaces the content of the clipboard with arbitrary data of the given MIME type.
+	pub fn set_data(&self, mime_type:&str, data:Vec<u8>) -> Result<(), ExternalError> {
+		self.inner.set_data(mime_type, data)
+	}
+
+	/// Returns the content of the clipboard converted to the given MIME type, if the clipboard
+	/// owner offers it.
+	pub fn data(&self, mime_type:&str) -> Option<Vec<u8>> { self.inner.data(mime_type) }
+
+	/// Clears the clipboard if this application owns it.
+	pub fn clear(&self) { self.inner.clear() }
+}
+
+#[cfg(not(any(
+	target_os = "linux",
+	target_os = "dragonfly",
+	target_os = "freebsd",
+	target_os = "netbsd",
+	target_os = "openbsd"
+)))]
+mod unsupported {
+	use url::Url;
+
+	use super::ClipboardImage;
+	use crate::error::{ExternalError, NotSupportedError};
+
+	pub(crate) struct PlatformClipboard;
+
+	impl PlatformClipboard {
+		pub fn set_text(&self, _text:&str) -> Result<(), ExternalError> {
+			Err(ExternalError::NotSupported(NotSupportedError::new()))
+		}
+
+		pub fn text(&self) -> Option<String> { None }
+
+		pub fn set_image(&self, _image:&ClipboardImage) -> Result<(), ExternalError> {
+			Err(ExternalError::NotSupported(NotSupportedError::new()))
+		}
+
+		pub fn image(&self) -> Option<ClipboardImage> { None }
+
+		pub fn set_uris(&self, _uris:&[Url]) -> Result<(), ExternalError> {
+			Err(ExternalError::NotSupported(NotSupportedError::new()))
+		}
+
+		pub fn uris(&self) -> Vec<Url> { Vec::new() }
+
+		pub fn set_data(&self, _mime_type:&str, _data:Vec<u8>) -> Result<(), ExternalError> {
+			Err(ExternalError::NotSupported(NotSupportedError::new()))
+		}
+
+		pub fn data(&self, _mime_type:&str) -> Option<Vec<u8>> { None }
+
+		pub fn clear(&self) {}
+	}
+}
@@ -40,10 +40,13 @@
 use std::{path::PathBuf, time::Instant};
 
 use crate::{
+  clipboard::ClipboardKind,
   dpi::{PhysicalPosition, PhysicalSize},
   keyboard::{self, ModifiersState},
+  monitor::MonitorHandle,
+  notification::NotificationId,
   platform_impl,
-  window::{Theme, WindowId},
+  window::{DragResult, Theme, WindowId, WindowStateFlags},
 };
 
 /// Describes a generic event.
@@ -142,6 +145,81 @@ pub enum Event<'a, T: 'static> {
   /// - **Other**: Unsupported.
   #[non_exhaustive]
   Reopen { has_visible_windows: bool },
+
+  /// Emitted when a monitor has been connected.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  MonitorAdded(MonitorHandle),
+
+  /// Emitted when a monitor has been disconnected.
+  ///
+  /// The handle can no longer be used to query up-to-date information about the monitor, but it
+  /// still compares equal to the handles previously obtained for it.
+  ///
+  /// Windows which are no longer visible on any monitor are moved onto the primary monitor,
+  /// keeping their offset from the top-left corner of the removed monitor when it fits in the
+  /// work area of the primary monitor. The usual [`WindowEvent::Moved`] events are emitted for
+  /// those windows.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux(Wayland):** Windows are left to the compositor to relocate.
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  MonitorRemoved(MonitorHandle),
+
+  /// Emitted when the geometry, the scale factor or the primary status of a monitor has changed.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  MonitorChanged(MonitorHandle),
+
+  /// Emitted when the content of a clipboard has been replaced, by this application or another
+  /// one.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  ClipboardChanged(ClipboardKind),
+
+  /// Emitted when the desktop session is about to end, because the user is logging out or the
+  /// computer is shutting down.
+  ///
+  /// The session manager doesn't wait for the application, so unsaved work should be protected
+  /// ahead of time with [`EventLoopWindowTarget::inhibit`], which lets the user cancel the logout.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Requires a session manager implementing `org.gnome.SessionManager`.
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  ///
+  /// [`EventLoopWindowTarget::inhibit`]: crate::event_loop::EventLoopWindowTarget::inhibit
+  SessionEnding,
+
+  /// Emitted when the computer goes to sleep or wakes up, or its power source changes.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Requires logind for sleep, UPower for the power source and
+  ///   power-profiles-daemon for the low-power mode.
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  Power(PowerEvent),
+
+  /// Emitted when the user responds to a notification shown by the application.
+  ///
+  /// See [`EventLoopWindowTarget::show_notification`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  ///
+  /// [`EventLoopWindowTarget::show_notification`]: crate::event_loop::EventLoopWindowTarget::show_notification
+  Notification {
+    id: NotificationId,
+    event: NotificationEvent,
+  },
 }
 
 impl<T: Clone> Clone for Event<'static, T> {
@@ -170,6 +248,16 @@ impl<T: Clone> Clone for Event<'static, T> {
       } => Reopen {
         has_visible_windows: *has_visible_windows,
       },
+      MonitorAdded(monitor) => MonitorAdded(monitor.clone()),
+      MonitorRemoved(monitor) => MonitorRemoved(monitor.clone()),
+      MonitorChanged(monitor) => MonitorChanged(monitor.clone()),
+      ClipboardChanged(kind) => ClipboardChanged(*kind),
+      SessionEnding => SessionEnding,
+      Power(event) => Power(*event),
+      Notification { id, event } => Notification {
+        id: *id,
+        event: event.clone(),
+      },
     }
   }
 }
@@ -194,6 +282,13 @@ impl<'a, T> Event<'a, T> {
       } => Ok(Reopen {
         has_visible_windows,
       }),
+      MonitorAdded(monitor) => Ok(MonitorAdded(monitor)),
+      MonitorRemoved(monitor) => Ok(MonitorRemoved(monitor)),
+      MonitorChanged(monitor) => Ok(MonitorChanged(monitor)),
+      ClipboardChanged(kind) => Ok(ClipboardChanged(kind)),
+      SessionEnding => Ok(SessionEnding),
+      Power(event) => Ok(Power(event)),
+      Notification { id, event } => Ok(Notification { id, event }),
     }
   }
 
@@ -220,10 +315,68 @@ impl<'a, T> Event<'a, T> {
       } => Some(Reopen {
         has_visible_windows,
       }),
+      MonitorAdded(monitor) => Some(MonitorAdded(monitor)),
+      MonitorRemoved(monitor) => Some(MonitorRemoved(monitor)),
+      MonitorChanged(monitor) => Some(MonitorChanged(monitor)),
+      ClipboardChanged(kind) => Some(ClipboardChanged(kind)),
+      SessionEnding => Some(SessionEnding),
+      Power(event) => Some(Power(event)),
+      Notification { id, event } => Some(Notification { id, event }),
     }
   }
 }
 
+/// Describes the response of the user to a notification.
+///
+/// See [`Event::Notification`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NotificationEvent {
+  /// The user clicked the notification.
+  Activated,
+  /// The user pressed the button added with [`Notification::with_action`], carrying its id.
+  ///
+  /// [`Notification::with_action`]: crate::notification::Notification::with_action
+  ActionInvoked(String),
+  /// The user replied from the notification, see [`Notification::with_reply`].
+  ///
+  /// [`Notification::with_reply`]: crate::notification::Notification::with_reply
+  Replied(String),
+  /// The user closed the notification.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Not reported in a sandbox.
+  Dismissed,
+}
+
+/// Describes a change of the power state of the computer.
+///
+/// See [`Event::Power`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerEvent {
+  /// The computer is about to go to sleep. Network connections will be interrupted.
+  Suspending,
+  /// The computer woke up from sleep.
+  Resumed,
+  /// The computer switched to another power source.
+  PowerSourceChanged(PowerSource),
+  /// The user enabled or disabled the low-power mode, in which applications should reduce their
+  /// activity, such as their frame rate.
+  LowPowerModeChanged(bool),
+}
+
+/// The source the computer is powered from.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerSource {
+  /// Mains power.
+  Ac,
+  /// The battery.
+  Battery,
+}
+
 /// Describes the reason the event loop is resuming.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
@@ -421,6 +574,39 @@ pub enum WindowEvent<'a> {
   ///
   /// - **Linux / macOS / Android / iOS:** Unsupported
   DecorationsClick,
+
+  /// The window's maximized, minimized, fullscreen, focused or tiled state has changed.
+  ///
+  /// The contained value is the complete new state of the window, not only the flags that changed.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS:** Tiled states are never reported.
+  /// - **Linux:** Tiled states require GTK 3.22.23 or newer and a window manager that reports them.
+  /// - **Android / iOS:** Unsupported
+  StateChanged(WindowStateFlags),
+
+  /// The window has been occluded (completely hidden from view) or is visible again.
+  ///
+  /// This is different to window visibility as it depends on whether the window is closed,
+  /// minimized, set invisible, or fully covered by other windows. Applications can use it to
+  /// pause rendering while nobody can see the window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Reported when the window is minimized or moved to another workspace. Being
+  ///   covered by other windows is only detected on X11 without a compositor.
+  /// - **Windows / Android / iOS:** Unsupported
+  Occluded(bool),
+
+  /// A drag started with [`Window::start_drag`] has ended.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / Android / iOS:** Unsupported
+  ///
+  /// [`Window::start_drag`]: crate::window::Window::start_drag
+  DragFinished(DragResult),
 }
 
 impl Clone for WindowEvent<'static> {
@@ -511,6 +697,9 @@ impl Clone for WindowEvent<'static> {
         unreachable!("Static event can't be about scale factor changing")
       }
       DecorationsClick => DecorationsClick,
+      StateChanged(state) => StateChanged(*state),
+      Occluded(occluded) => Occluded(*occluded),
+      DragFinished(result) => DragFinished(*result),
     }
   }
 }
@@ -596,6 +785,9 @@ impl<'a> WindowEvent<'a> {
       ThemeChanged(theme) => Some(ThemeChanged(theme)),
       ScaleFactorChanged { .. } => None,
       DecorationsClick => Some(DecorationsClick),
+      StateChanged(state) => Some(StateChanged(state)),
+      Occluded(occluded) => Some(Occluded(occluded)),
+      DragFinished(result) => Some(DragFinished(result)),
     }
   }
 }
@@ -887,6 +1079,75 @@ pub type AxisId = u32;
 /// Identifier for a specific button on some device.
 pub type ButtonId = u32;
 
+/// Standardized button and axis identifiers reported by game controllers.
+///
+/// Game controllers report their buttons through [`DeviceEvent::Button`] and their sticks and
+/// triggers through [`DeviceEvent::Motion`] using these identifiers, whatever the layout of the
+/// physical device. Stick axes range from `-1.0` to `1.0` with positive values pointing right and
+/// down, trigger axes range from `0.0` to `1.0`.
+///
+/// Buttons and axes that have no standard equivalent are reported as [`OTHER`](gamepad::OTHER)
+/// plus the platform code of the button or axis.
+///
+/// ## Platform-specific
+///
+/// - **Linux:** Read from the evdev devices in `/dev/input`, which requires read access to them.
+/// - **Windows / macOS / iOS / Android:** Game controllers are not reported.
+pub mod gamepad {
+  use super::{AxisId, ButtonId};
+
+  /// The bottom face button (A on Xbox controllers, Cross on PlayStation controllers).
+  pub const BUTTON_SOUTH: ButtonId = 0;
+  /// The right face button (B on Xbox controllers, Circle on PlayStation controllers).
+  pub const BUTTON_EAST: ButtonId = 1;
+  /// The left face button (X on Xbox controllers, Square on PlayStation controllers).
+  pub const BUTTON_WEST: ButtonId = 2;
+  /// The top face button (Y on Xbox controllers, Triangle on PlayStation controllers).
+  pub const BUTTON_NORTH: ButtonId = 3;
+  /// The left shoulder button.
+  pub const BUTTON_LEFT_BUMPER: ButtonId = 4;
+  /// The right shoulder button.
+  pub const BUTTON_RIGHT_BUMPER: ButtonId = 5;
+  /// The left trigger, for controllers whose triggers are digital.
+  pub const BUTTON_LEFT_TRIGGER: ButtonId = 6;
+  /// The right trigger, for controllers whose triggers are digital.
+  pub const BUTTON_RIGHT_TRIGGER: ButtonId = 7;
+  /// The select, back, view or share button.
+  pub const BUTTON_SELECT: ButtonId = 8;
+  /// The start, menu or options button.
+  pub const BUTTON_START: ButtonId = 9;
+  /// Pressing the left stick.
+  pub const BUTTON_LEFT_STICK: ButtonId = 10;
+  /// Pressing the right stick.
+  pub const BUTTON_RIGHT_STICK: ButtonId = 11;
+  /// Up on the directional pad.
+  pub const BUTTON_DPAD_UP: ButtonId = 12;
+  /// Down on the directional pad.
+  pub const BUTTON_DPAD_DOWN: ButtonId = 13;
+  /// Left on the directional pad.
+  pub const BUTTON_DPAD_LEFT: ButtonId = 14;
+  /// Right on the directional pad.
+  pub const BUTTON_DPAD_RIGHT: ButtonId = 15;
+  /// The vendor button (Xbox or PlayStation logo).
+  pub const BUTTON_MODE: ButtonId = 16;
+
+  /// The horizontal axis of the left stick.
+  pub const AXIS_LEFT_STICK_X: AxisId = 0;
+  /// The vertical axis of the left stick.
+  pub const AXIS_LEFT_STICK_Y: AxisId = 1;
+  /// The horizontal axis of the right stick.
+  pub const AXIS_RIGHT_STICK_X: AxisId = 2;
+  /// The vertical axis of the right stick.
+  pub const AXIS_RIGHT_STICK_Y: AxisId = 3;
+  /// The left trigger, for controllers whose triggers are analog.
+  pub const AXIS_LEFT_TRIGGER: AxisId = 4;
+  /// The right trigger, for controllers whose triggers are analog.
+  pub const AXIS_RIGHT_TRIGGER: AxisId = 5;
+
+  /// Offset added to the platform code of buttons and axes without a standard equivalent.
+  pub const OTHER: u32 = 0x10000;
+}
+
 /// Describes the input state of a key.
 #[non_exhaustive]
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
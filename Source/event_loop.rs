@@ -13,14 +13,24 @@
 //! [create_proxy]: crate::event_loop::EventLoop::create_proxy
 //! [event_loop_proxy]: crate::event_loop::EventLoopProxy
 //! [send_event]: crate::event_loop::EventLoopProxy::send_event
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::{error, fmt, marker::PhantomData, ops::Deref};
 
+#[cfg(not(any(
+  target_os = "linux",
+  target_os = "dragonfly",
+  target_os = "freebsd",
+  target_os = "netbsd",
+  target_os = "openbsd",
+)))]
+use crate::clipboard::PlatformClipboard;
 use crate::{
+  clipboard::{Clipboard, ClipboardKind},
   dpi::PhysicalPosition,
   error::ExternalError,
   event::Event,
   monitor::MonitorHandle,
+  notification::{Notification, NotificationId},
   platform_impl,
   window::{ProgressBarState, Theme},
 };
@@ -293,7 +303,7 @@ impl<T> EventLoopWindowTarget<T> {
   /// ## Platform-specific
   ///
   /// - **Windows:** Unsupported. Use the Progress Bar Function Available in Window (Windows can have different progress bars for different window)
-  /// - **Linux:** Only supported desktop environments with `libunity` (e.g. GNOME).
+  /// - **Linux:** Only supported by docks implementing the Unity `LauncherEntry` D-Bus protocol (e.g. KDE Plasma, Dash-to-Dock, elementary).
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_progress_bar(&self, _progress: ProgressBarState) {
@@ -319,6 +329,191 @@ impl<T> EventLoopWindowTarget<T> {
     ))]
     self.p.set_theme(_theme)
   }
+
+  /// Asks the session manager to prevent the actions in `flags` until the returned
+  /// [`InhibitCookie`] is dropped, for example while there are unsaved changes.
+  ///
+  /// `reason` is shown to the user when they attempt one of the inhibited actions.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Requires a session manager implementing `org.gnome.SessionManager`, or the
+  ///   inhibit portal when running in a sandbox.
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn inhibit(&self, _flags: InhibitFlags, _reason: &str) -> Result<InhibitCookie, ExternalError> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    return self
+      .p
+      .inhibit(_flags, _reason)
+      .map(|inner| InhibitCookie { _inner: inner });
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    )))]
+    return Err(ExternalError::NotSupported(
+      crate::error::NotSupportedError::new(),
+    ));
+  }
+
+  /// Returns how long the user hasn't used any input device, for example to mark them as away.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux(X11):** Requires the `XScreenSaver` extension, unless running on GNOME or KDE.
+  /// - **Linux(Wayland):** Only supported on GNOME and KDE.
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn user_idle_time(&self) -> Result<Duration, ExternalError> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    return self.p.user_idle_time();
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    )))]
+    return Err(ExternalError::NotSupported(
+      crate::error::NotSupportedError::new(),
+    ));
+  }
+
+  /// Shows a desktop notification. The user's response is reported with
+  /// [`Event::Notification`] carrying the returned id.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Requires a notification server implementing `org.freedesktop.Notifications`, or
+  ///   the notification portal when running in a sandbox.
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn show_notification(
+    &self,
+    _notification: &Notification,
+  ) -> Result<NotificationId, ExternalError> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    return self.p.show_notification(_notification, None);
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    )))]
+    return Err(ExternalError::NotSupported(
+      crate::error::NotSupportedError::new(),
+    ));
+  }
+
+  /// Replaces the notification `id` with `notification`, keeping its place on screen.
+  ///
+  /// If the notification was already closed, it is shown again, possibly with a new id.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn replace_notification(
+    &self,
+    _id: NotificationId,
+    _notification: &Notification,
+  ) -> Result<NotificationId, ExternalError> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    return self.p.show_notification(_notification, Some(_id));
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    )))]
+    return Err(ExternalError::NotSupported(
+      crate::error::NotSupportedError::new(),
+    ));
+  }
+
+  /// Closes the notification `id`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn close_notification(&self, _id: NotificationId) -> Result<(), ExternalError> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    return self.p.close_notification(_id);
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    )))]
+    return Err(ExternalError::NotSupported(
+      crate::error::NotSupportedError::new(),
+    ));
+  }
+
+  /// Returns a handle to the given system clipboard.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported, see [`Clipboard`].
+  #[inline]
+  pub fn clipboard(&self, kind: ClipboardKind) -> Clipboard {
+    Clipboard {
+      #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+      ))]
+      inner: self.p.clipboard(kind),
+      #[cfg(not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+      )))]
+      inner: PlatformClipboard,
+      kind,
+    }
+  }
 }
 
 #[cfg(feature = "rwh_05")]
@@ -410,3 +605,35 @@ impl Default for DeviceEventFilter {
     Self::Unfocused
   }
 }
+
+bitflags! {
+  /// The actions of the user that [`EventLoopWindowTarget::inhibit`] can prevent.
+  #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+  pub struct InhibitFlags: u32 {
+    /// Logging out or shutting down the computer.
+    const LOGOUT = 1 << 0;
+    /// Switching to another user.
+    const SWITCH_USER = 1 << 1;
+    /// Suspending or hibernating the computer.
+    const SUSPEND = 1 << 2;
+  }
+}
+
+/// Keeps the actions inhibited with [`EventLoopWindowTarget::inhibit`] or
+/// [`Window::inhibit_idle`](crate::window::Window::inhibit_idle) prevented until dropped.
+pub struct InhibitCookie {
+  #[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+  ))]
+  pub(crate) _inner: platform_impl::InhibitCookie,
+}
+
+impl fmt::Debug for InhibitCookie {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.pad("InhibitCookie { .. }")
+  }
+}
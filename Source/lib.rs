@@ -174,11 +174,13 @@ pub use dpi;
 
 #[macro_use]
 pub mod error;
+pub mod clipboard;
 pub mod event;
 pub mod event_loop;
 mod icon;
 pub mod keyboard;
 pub mod monitor;
+pub mod notification;
 mod platform_impl;
 
 pub mod window;
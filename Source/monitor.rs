@@ -97,6 +97,28 @@ impl std::fmt::Display for VideoMode {
 	}
 }
 
+/// Describes the physical arrangement of the subpixels of a monitor.
+///
+/// Can be acquired with [`MonitorHandle::subpixel_layout`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SubpixelLayout {
+	/// The layout is not known.
+	#[default]
+	Unknown,
+	/// Not organized in this way.
+	None,
+	/// The layout is horizontal, the order is RGB.
+	HorizontalRgb,
+	/// The layout is horizontal, the order is BGR.
+	HorizontalBgr,
+	/// The layout is vertical, the order is RGB.
+	VerticalRgb,
+	/// The layout is vertical, the order is BGR.
+	VerticalBgr,
+}
+
 /// Handle to a monitor.
 ///
 /// Allows you to retrieve information about a given monitor and can be used in
@@ -135,10 +157,84 @@ impl MonitorHandle {
 	#[inline]
 	pub fn scale_factor(&self) -> f64 { self.inner.scale_factor() }
 
+	/// Returns the position and size of the part of the monitor that isn't
+	/// covered by panels and docks, or of the whole monitor where it isn't known.
+	pub(crate) fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+		#[cfg(any(
+			target_os = "linux",
+			target_os = "dragonfly",
+			target_os = "freebsd",
+			target_os = "netbsd",
+			target_os = "openbsd"
+		))]
+		return self.inner.work_area();
+
+		#[cfg(not(any(
+			target_os = "linux",
+			target_os = "dragonfly",
+			target_os = "freebsd",
+			target_os = "netbsd",
+			target_os = "openbsd"
+		)))]
+		return (self.position(), self.size());
+	}
+
 	/// Returns all fullscreen video modes supported by this monitor.
 	///
 	/// ## Platform-specific
 	/// - **Linux:** Unsupported. This will always return empty iterator.
 	#[inline]
 	pub fn video_modes(&self) -> impl Iterator<Item = VideoMode> { self.inner.video_modes() }
+
+	/// Returns the physical `(width, height)` of the monitor in millimeters.
+	///
+	/// Returns `None` if the size is unknown, which is common for projectors
+	/// and virtual displays.
+	///
+	/// ## Platform-specific
+	///
+	/// - **iOS / Android:** Unsupported, always returns `None`.
+	#[inline]
+	pub fn physical_size_mm(&self) -> Option<(u32, u32)> { self.inner.physical_size_mm() }
+
+	/// Returns the current refresh rate of the monitor in millihertz.
+	///
+	/// Returns `None` if the refresh rate is unknown.
+	///
+	/// ## Platform-specific
+	///
+	/// - **iOS / Android:** Unsupported, always returns `None`.
+	#[inline]
+	pub fn refresh_rate_millihertz(&self) -> Option<u32> { self.inner.refresh_rate_millihertz() }
+
+	/// Returns the name of the monitor manufacturer.
+	///
+	/// ## Platform-specific
+	///
+	/// - **Linux(X11):** GTK doesn't report it, always returns `None`.
+	/// - **Windows / macOS / iOS / Android:** Unsupported, always returns
+	///   `None`.
+	#[inline]
+	pub fn manufacturer(&self) -> Option<String> { self.inner.manufacturer() }
+
+	/// Returns the name of the connector the monitor is plugged into, for
+	/// example `DP-1` or `HDMI-A-1`.
+	///
+	/// ## Platform-specific
+	///
+	/// - **Windows:** Returns the GDI device name, for example
+	///   `\\.\DISPLAY1`.
+	/// - **Linux(Wayland) / macOS / iOS / Android:** Unsupported, always
+	///   returns `None`.
+	#[inline]
+	pub fn connector_name(&self) -> Option<String> { self.inner.connector_name() }
+
+	/// Returns the subpixel layout of the monitor.
+	///
+	/// ## Platform-specific
+	///
+	/// - **Windows / macOS / iOS / Android:** Unsupported, always returns
+	///   [`SubpixelLayout::Unknown`].
+	#[inline]
+	pub fn subpixel_layout(&self) -> SubpixelLayout { self.inner.subpixel_layout() }
 }
@@ -0,0 +1,100 @@
+// Copyright 2014-2021 The winit contributors
+// Copyright 2021-2023 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `Notification` struct and associated types.
+//!
+//! A [`Notification`] is shown with [`EventLoopWindowTarget::show_notification`], which returns the
+//! [`NotificationId`] used to replace or close it. The user's response is reported through
+//! [`Event::Notification`].
+//!
+//! [`EventLoopWindowTarget::show_notification`]: crate::event_loop::EventLoopWindowTarget::show_notification
+//! [`Event::Notification`]: crate::event::Event::Notification
+use crate::icon::Icon;
+
+/// Identifies a notification shown by the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NotificationId(pub(crate) u32);
+
+/// How urgent a [`Notification`] is, which decides how prominently it is shown.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Urgency {
+	/// The notification may be shown without interrupting the user, or not at all.
+	Low,
+	#[default]
+	Normal,
+	/// The notification stays visible until the user dismisses it.
+	Critical,
+}
+
+/// A desktop notification.
+///
+/// ## Platform-specific
+///
+/// - **Windows / macOS / iOS / Android:** Unsupported.
+#[derive(Debug, Clone, Default)]
+pub struct Notification {
+	pub(crate) title:String,
+	pub(crate) body:String,
+	pub(crate) icon:Option<Icon>,
+	pub(crate) urgency:Urgency,
+	pub(crate) actions:Vec<(String, String)>,
+	pub(crate) reply_placeholder:Option<String>,
+}
+
+impl Notification {
+	/// Creates a notification with the given title.
+	pub fn new(title:impl Into<String>) -> Self { Self { title:title.into(), ..Default::default() } }
+
+	/// Sets the text shown below the title.
+	pub fn with_body(mut self, body:impl Into<String>) -> Self {
+		self.body = body.into();
+		self
+	}
+
+	/// Sets the image shown next to the text.
+	pub fn with_icon(mut self, icon:Icon) -> Self {
+		self.icon = Some(icon);
+		self
+	}
+
+	/// Sets the urgency of the notification. Defaults to [`Urgency::Normal`].
+	pub fn with_urgency(mut self, urgency:Urgency) -> Self {
+		self.urgency = urgency;
+		self
+	}
+
+	/// Adds a button labelled `label`. Pressing it is reported with
+	/// [`NotificationEvent::ActionInvoked`] carrying `id`.
+	///
+	/// [`NotificationEvent::ActionInvoked`]: crate::event::NotificationEvent::ActionInvoked
+	pub fn with_action(mut self, id:impl Into<String>, label:impl Into<String>) -> Self {
+		self.actions.push((id.into(), label.into()));
+		self
+	}
+
+	/// Adds a text field to reply from the notification, showing `placeholder` while empty. The
+	/// reply is reported with [`NotificationEvent::Replied`].
+	///
+	/// ## Platform-specific
+	///
+	/// - **Linux:** Only supported by notification servers with the `inline-reply` capability
+	///   (e.g. KDE Plasma), ignored otherwise. Unsupported in a sandbox.
+	///
+	/// [`NotificationEvent::Replied`]: crate::event::NotificationEvent::Replied
+	pub fn with_reply(mut self, placeholder:impl Into<String>) -> Self {
+		self.reply_placeholder = Some(placeholder.into());
+		self
+	}
+
+	/// Returns the title of the notification.
+	pub fn title(&self) -> &str { &self.title }
+
+	/// Returns the text shown below the title.
+	pub fn body(&self) -> &str { &self.body }
+
+	/// Returns the urgency of the notification.
+	pub fn urgency(&self) -> Urgency { self.urgency }
+}
@@ -13,12 +13,15 @@
 use std::{os::raw::c_int, sync::Arc};
 
 use self::x11::xdisplay::XConnection;
+#[cfg(feature = "layer-shell")]
+use crate::platform_impl::wayland::layer_shell::LayerShellRequest;
 pub use crate::platform_impl::EventLoop as UnixEventLoop;
 // XConnection utilities
 #[doc(hidden)]
 pub use crate::platform_impl::x11;
 use crate::{
 	error::{ExternalError, OsError},
+	event::DeviceId,
 	event_loop::{EventLoopBuilder, EventLoopWindowTarget},
 	monitor::MonitorHandle,
 	platform_impl::{Parent, Window as UnixWindow, x11::xdisplay::XError},
@@ -85,6 +88,63 @@ pub trait WindowExtUnix {
 	fn set_skip_taskbar(&self, skip:bool) -> Result<(), ExternalError>;
 
 	fn set_badge_count(&self, count:Option<i64>, desktop_filename:Option<String>);
+
+	/// Brings the window to front and focuses it with an activation token,
+	/// which proves to the window manager that the request follows an action of
+	/// the user.
+	///
+	/// Tokens are handed over by the application that asked for the window to
+	/// be focused, for example a second instance of the application or a
+	/// notification daemon. The token the process was launched with is
+	/// automatically used by the first window.
+	fn focus_with_activation_token(&self, token:ActivationToken);
+
+	/// Moves the layer surface to another layer.
+	///
+	/// Returns [`ExternalError::NotSupported`] if the window isn't a layer
+	/// surface, see [`WindowBuilderExtUnix::with_layer_shell`].
+	#[cfg(feature = "layer-shell")]
+	fn set_layer(&self, layer:Layer) -> Result<(), ExternalError>;
+
+	/// Sets the screen edges the layer surface is anchored to.
+	///
+	/// Returns [`ExternalError::NotSupported`] if the window isn't a layer
+	/// surface.
+	#[cfg(feature = "layer-shell")]
+	fn set_anchor(&self, anchor:Anchor) -> Result<(), ExternalError>;
+
+	/// Sets the distance in logical pixels between the layer surface and the
+	/// edges it is anchored to.
+	///
+	/// Returns [`ExternalError::NotSupported`] if the window isn't a layer
+	/// surface.
+	#[cfg(feature = "layer-shell")]
+	fn set_margin(&self, top:i32, right:i32, bottom:i32, left:i32) -> Result<(), ExternalError>;
+
+	/// Sets the exclusive zone of the layer surface, see
+	/// [`WindowBuilderExtUnix::with_exclusive_zone`].
+	///
+	/// Returns [`ExternalError::NotSupported`] if the window isn't a layer
+	/// surface.
+	#[cfg(feature = "layer-shell")]
+	fn set_exclusive_zone(&self, zone:i32) -> Result<(), ExternalError>;
+
+	/// Sets how the layer surface receives keyboard focus.
+	///
+	/// Returns [`ExternalError::NotSupported`] if the window isn't a layer
+	/// surface.
+	#[cfg(feature = "layer-shell")]
+	fn set_keyboard_interactivity(
+		&self,
+		interactivity:KeyboardInteractivity,
+	) -> Result<(), ExternalError>;
+
+	/// Moves the layer surface to another monitor.
+	///
+	/// Returns [`ExternalError::NotSupported`] if the window isn't a layer
+	/// surface.
+	#[cfg(feature = "layer-shell")]
+	fn set_output(&self, monitor:&MonitorHandle) -> Result<(), ExternalError>;
 }
 
 impl WindowExtUnix for Window {
@@ -108,11 +168,95 @@ impl WindowExtUnix for Window {
 	fn set_badge_count(&self, count:Option<i64>, desktop_filename:Option<String>) {
 		self.window.set_badge_count(count, desktop_filename);
 	}
+
+	fn focus_with_activation_token(&self, token:ActivationToken) {
+		self.window.focus_with_activation_token(token.into_raw())
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn set_layer(&self, layer:Layer) -> Result<(), ExternalError> {
+		self.window.set_layer_shell(LayerShellRequest::Layer(layer))
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn set_anchor(&self, anchor:Anchor) -> Result<(), ExternalError> {
+		self.window.set_layer_shell(LayerShellRequest::Anchor(anchor))
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn set_margin(&self, top:i32, right:i32, bottom:i32, left:i32) -> Result<(), ExternalError> {
+		self.window.set_layer_shell(LayerShellRequest::Margin(top, right, bottom, left))
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn set_exclusive_zone(&self, zone:i32) -> Result<(), ExternalError> {
+		self.window.set_layer_shell(LayerShellRequest::ExclusiveZone(zone))
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn set_keyboard_interactivity(
+		&self,
+		interactivity:KeyboardInteractivity,
+	) -> Result<(), ExternalError> {
+		self.window.set_layer_shell(LayerShellRequest::KeyboardInteractivity(interactivity))
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn set_output(&self, monitor:&MonitorHandle) -> Result<(), ExternalError> {
+		self.window.set_layer_shell(LayerShellRequest::Output(monitor.inner.monitor.clone()))
+	}
 }
 
 pub trait WindowBuilderExtUnix {
 	/// Whether to create the window icon with the taskbar icon or not.
+	///
+	/// Skipping the taskbar also skips the pager.
 	fn with_skip_taskbar(self, skip:bool) -> WindowBuilder;
+
+	/// Whether to hide the window from pagers and workspace switchers.
+	///
+	/// Default is `false`.
+	fn with_skip_pager(self, skip:bool) -> WindowBuilder;
+
+	/// Whether the window demands the attention of the user when it is shown,
+	/// usually by highlighting its taskbar entry.
+	///
+	/// Default is `false`. Use
+	/// [`Window::request_user_attention`](crate::window::Window::request_user_attention)
+	/// to change it later.
+	fn with_demands_attention(self, demands_attention:bool) -> WindowBuilder;
+
+	/// Sets the type of the window, which the window manager uses to decide
+	/// how to decorate, stack and place it.
+	///
+	/// Default is [`WindowType::Normal`].
+	///
+	/// ## Platform-specific
+	///
+	/// - **Wayland:** Most compositors ignore the window type.
+	fn with_window_type(self, window_type:WindowType) -> WindowBuilder;
+
+	/// Sets the class of the window, overriding the one derived from the
+	/// application id set with [`EventLoopBuilderExtUnix::with_app_id`].
+	///
+	/// Window managers use it to group windows and to match their rules, so
+	/// that windows of the same application can be told apart.
+	///
+	/// ## Platform-specific
+	///
+	/// - **X11:** Sets the `WM_CLASS` property to `instance` and `general`.
+	/// - **Wayland:** Sets the app id of the window to `general`, `instance` is
+	///   ignored.
+	fn with_name(self, general:impl Into<String>, instance:impl Into<String>) -> WindowBuilder;
+
+	/// Sets a unique identifier of the window among the windows of the
+	/// application, which session managers use to restore it.
+	///
+	/// ## Platform-specific
+	///
+	/// - **X11:** Sets the `WM_WINDOW_ROLE` property.
+	/// - **Wayland:** Unsupported.
+	fn with_role(self, role:impl Into<String>) -> WindowBuilder;
 	/// Set this window as a transient dialog for `parent`
 	/// <https://gtk-rs.org/gtk3-rs/stable/latest/docs/gdk/struct.Window.html#method.set_transient_for>
 	fn with_transient_for(self, parent:&impl gtk::glib::IsA<gtk::Window>) -> WindowBuilder;
@@ -156,6 +300,55 @@ pub trait WindowBuilderExtUnix {
 	/// Whether to create a vertical `gtk::Box` and add it as the sole child of
 	/// this window. Created by default.
 	fn with_default_vbox(self, add:bool) -> WindowBuilder;
+
+	/// Creates the window as a layer surface on the given layer instead of an
+	/// ordinary toplevel, for panels, docks, overlays and wallpapers.
+	///
+	/// Layer surfaces are placed by the compositor according to their anchors
+	/// and margins, they have no decorations and ignore the requested position.
+	///
+	/// ## Platform-specific
+	///
+	/// - **X11:** Unsupported, building the window fails with an [`OsError`].
+	/// - **Wayland:** Requires a compositor implementing `wlr-layer-shell`, such
+	///   as wlroots-based compositors or KWin. Otherwise building the window
+	///   fails with an [`OsError`].
+	#[cfg(feature = "layer-shell")]
+	fn with_layer_shell(self, layer:Layer) -> WindowBuilder;
+
+	/// Sets the screen edges the layer surface is anchored to. Anchoring to two
+	/// opposite edges stretches the surface between them.
+	///
+	/// Default is no anchor, which centers the surface on the output.
+	#[cfg(feature = "layer-shell")]
+	fn with_anchor(self, anchor:Anchor) -> WindowBuilder;
+
+	/// Sets the distance in logical pixels between the layer surface and the
+	/// edges it is anchored to.
+	///
+	/// Default is `0` on every edge.
+	#[cfg(feature = "layer-shell")]
+	fn with_margin(self, top:i32, right:i32, bottom:i32, left:i32) -> WindowBuilder;
+
+	/// Sets the size in logical pixels of the area the layer surface reserves
+	/// along the edge it is anchored to, that other windows won't cover.
+	///
+	/// `0` doesn't reserve any area but avoids the areas reserved by other
+	/// surfaces, `-1` ignores them. Default is `0`.
+	#[cfg(feature = "layer-shell")]
+	fn with_exclusive_zone(self, zone:i32) -> WindowBuilder;
+
+	/// Sets how the layer surface receives keyboard focus.
+	///
+	/// Default is [`KeyboardInteractivity::None`].
+	#[cfg(feature = "layer-shell")]
+	fn with_keyboard_interactivity(self, interactivity:KeyboardInteractivity) -> WindowBuilder;
+
+	/// Sets the monitor the layer surface is shown on.
+	///
+	/// Default lets the compositor choose, usually the focused monitor.
+	#[cfg(feature = "layer-shell")]
+	fn with_output(self, monitor:&MonitorHandle) -> WindowBuilder;
 }
 
 impl WindowBuilderExtUnix for WindowBuilder {
@@ -165,6 +358,36 @@ impl WindowBuilderExtUnix for WindowBuilder {
 		self
 	}
 
+	fn with_skip_pager(mut self, skip:bool) -> WindowBuilder {
+		self.platform_specific.skip_pager = skip;
+
+		self
+	}
+
+	fn with_demands_attention(mut self, demands_attention:bool) -> WindowBuilder {
+		self.platform_specific.demands_attention = demands_attention;
+
+		self
+	}
+
+	fn with_window_type(mut self, window_type:WindowType) -> WindowBuilder {
+		self.platform_specific.window_type = window_type;
+
+		self
+	}
+
+	fn with_name(mut self, general:impl Into<String>, instance:impl Into<String>) -> WindowBuilder {
+		self.platform_specific.name = Some((general.into(), instance.into()));
+
+		self
+	}
+
+	fn with_role(mut self, role:impl Into<String>) -> WindowBuilder {
+		self.platform_specific.role = Some(role.into());
+
+		self
+	}
+
 	fn with_transient_for(mut self, parent:&impl gtk::glib::IsA<gtk::Window>) -> WindowBuilder {
 		use gtk::glib::Cast;
 
@@ -208,6 +431,127 @@ impl WindowBuilderExtUnix for WindowBuilder {
 
 		self
 	}
+
+	#[cfg(feature = "layer-shell")]
+	fn with_layer_shell(mut self, layer:Layer) -> WindowBuilder {
+		self.platform_specific.layer_shell.layer = Some(layer);
+
+		self
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn with_anchor(mut self, anchor:Anchor) -> WindowBuilder {
+		self.platform_specific.layer_shell.anchor = anchor;
+
+		self
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn with_margin(mut self, top:i32, right:i32, bottom:i32, left:i32) -> WindowBuilder {
+		self.platform_specific.layer_shell.margin = (top, right, bottom, left);
+
+		self
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn with_exclusive_zone(mut self, zone:i32) -> WindowBuilder {
+		self.platform_specific.layer_shell.exclusive_zone = zone;
+
+		self
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn with_keyboard_interactivity(mut self, interactivity:KeyboardInteractivity) -> WindowBuilder {
+		self.platform_specific.layer_shell.keyboard_interactivity = interactivity;
+
+		self
+	}
+
+	#[cfg(feature = "layer-shell")]
+	fn with_output(mut self, monitor:&MonitorHandle) -> WindowBuilder {
+		self.platform_specific.layer_shell.output = Some(monitor.inner.monitor.clone());
+
+		self
+	}
+}
+
+/// The type of a window, set as `_NET_WM_WINDOW_TYPE`, see
+/// [`WindowBuilderExtUnix::with_window_type`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WindowType {
+	/// An ordinary top-level window.
+	#[default]
+	Normal,
+	/// A dialog, usually transient for another window.
+	Dialog,
+	/// A panel or dock, kept on top of other windows.
+	Dock,
+	/// A toolbar torn off from the main window.
+	Toolbar,
+	/// A menu torn off from the main window.
+	Menu,
+	/// A small persistent utility window, such as a palette or toolbox.
+	Utility,
+	/// A splash screen shown while the application is starting.
+	Splash,
+	/// A menu opened from a menubar.
+	DropdownMenu,
+	/// A menu opened by a right click.
+	PopupMenu,
+	/// A tooltip.
+	Tooltip,
+	/// A notification bubble.
+	Notification,
+}
+
+/// The layer of a layer surface, see
+/// [`WindowBuilderExtUnix::with_layer_shell`].
+///
+/// Layers are stacked in the order of the variants, ordinary windows are
+/// between [`Layer::Bottom`] and [`Layer::Top`].
+#[cfg(feature = "layer-shell")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layer {
+	/// Below everything else, for wallpapers.
+	Background,
+	/// Below ordinary windows, for desktop widgets.
+	Bottom,
+	/// Above ordinary windows, for panels and docks.
+	Top,
+	/// Above everything else including fullscreen windows, for lock screens
+	/// and on-screen displays.
+	Overlay,
+}
+
+#[cfg(feature = "layer-shell")]
+bitflags! {
+	/// The screen edges a layer surface is anchored to.
+	#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+	pub struct Anchor: u32 {
+		/// Anchored to the top edge of the output.
+		const TOP = 1 << 0;
+		/// Anchored to the right edge of the output.
+		const RIGHT = 1 << 1;
+		/// Anchored to the bottom edge of the output.
+		const BOTTOM = 1 << 2;
+		/// Anchored to the left edge of the output.
+		const LEFT = 1 << 3;
+	}
+}
+
+/// How a layer surface receives keyboard focus.
+#[cfg(feature = "layer-shell")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum KeyboardInteractivity {
+	/// The surface never receives keyboard focus.
+	#[default]
+	None,
+	/// The surface grabs the keyboard as long as it is mapped on the
+	/// [`Layer::Top`] or [`Layer::Overlay`] layers.
+	Exclusive,
+	/// The surface can be focused like an ordinary window.
+	OnDemand,
 }
 
 /// Additional methods on `EventLoopWindowTarget` that are specific to Unix.
@@ -232,7 +576,21 @@ pub trait EventLoopWindowTargetExtUnix {
 	fn gtk_app(&self) -> &gtk::Application;
 
 	/// Sets the badge count on the taskbar
+	///
+	/// Only supported by docks implementing the Unity `LauncherEntry` D-Bus
+	/// protocol. `desktop_filename` defaults to the application id followed by
+	/// `.desktop`.
 	fn set_badge_count(&self, count:Option<i64>, desktop_filename:Option<String>);
+
+	/// Obtains an activation token to hand to a process launched by the
+	/// application, in the `XDG_ACTIVATION_TOKEN` and `DESKTOP_STARTUP_ID`
+	/// environment variables, so that it may focus its first window.
+	///
+	/// ## Platform-specific
+	///
+	/// - **Wayland:** Requires GTK 3.24.31 or newer and a compositor
+	///   implementing `xdg-activation`.
+	fn request_activation_token(&self) -> Result<ActivationToken, ExternalError>;
 }
 
 impl<T> EventLoopWindowTargetExtUnix for EventLoopWindowTarget<T> {
@@ -273,6 +631,30 @@ impl<T> EventLoopWindowTargetExtUnix for EventLoopWindowTarget<T> {
 	fn set_badge_count(&self, count:Option<i64>, desktop_filename:Option<String>) {
 		self.p.set_badge_count(count, desktop_filename);
 	}
+
+	#[inline]
+	fn request_activation_token(&self) -> Result<ActivationToken, ExternalError> {
+		self.p.request_activation_token().map(ActivationToken::from_raw)
+	}
+}
+
+/// A token proving that a request to focus a window follows an action of the
+/// user, as defined by the `xdg-activation` protocol on Wayland and by startup
+/// notification on X11.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActivationToken {
+	token:String,
+}
+
+impl ActivationToken {
+	/// Creates a token from its raw value, as received from another process.
+	pub fn from_raw(token:String) -> Self { Self { token } }
+
+	/// Returns the raw value of the token, to pass to another process.
+	pub fn as_raw(&self) -> &str { &self.token }
+
+	/// Consumes the token and returns its raw value.
+	pub fn into_raw(self) -> String { self.token }
 }
 
 unsafe extern fn x_error_callback(
@@ -303,3 +685,33 @@ impl MonitorHandleExtUnix for MonitorHandle {
 	#[inline]
 	fn gdk_monitor(&self) -> &gtk::gdk::Monitor { &self.inner.monitor }
 }
+
+/// Additional methods on `DeviceId` that are specific to Unix.
+///
+/// Only game controllers are tracked, these return `None` for other devices.
+/// The metadata stays available after the device has been removed.
+///
+/// ## Platform-specific
+///
+/// - **Dragonfly / FreeBSD / NetBSD / OpenBSD:** Unsupported, always returns `None`.
+pub trait DeviceIdExtUnix {
+	/// Returns the name the kernel reports for the device.
+	fn name(&self) -> Option<String>;
+
+	/// Returns the USB or Bluetooth vendor id of the device.
+	fn vendor_id(&self) -> Option<u16>;
+
+	/// Returns the USB or Bluetooth product id of the device.
+	fn product_id(&self) -> Option<u16>;
+}
+
+impl DeviceIdExtUnix for DeviceId {
+	#[inline]
+	fn name(&self) -> Option<String> { self.0.name() }
+
+	#[inline]
+	fn vendor_id(&self) -> Option<u16> { self.0.vendor_id() }
+
+	#[inline]
+	fn product_id(&self) -> Option<u16> { self.0.product_id() }
+}
@@ -688,6 +688,15 @@ impl Window {
 		Err(error::ExternalError::NotSupported(error::NotSupportedError::new()))
 	}
 
+	pub fn start_drag(
+		&self,
+		_items:Vec<window::DragItem>,
+		_allowed_actions:window::DragActions,
+		_icon:Option<window::Icon>,
+	) -> Result<(), error::ExternalError> {
+		Err(error::ExternalError::NotSupported(error::NotSupportedError::new()))
+	}
+
 	pub fn set_background_color(&self, _color:Option<crate::window::RGBA>) {}
 
 	pub fn set_ignore_cursor_events(&self, _ignore:bool) -> Result<(), error::ExternalError> {
@@ -837,6 +846,16 @@ impl MonitorHandle {
 
 		v.into_iter()
 	}
+
+	pub fn physical_size_mm(&self) -> Option<(u32, u32)> { None }
+
+	pub fn refresh_rate_millihertz(&self) -> Option<u32> { None }
+
+	pub fn manufacturer(&self) -> Option<String> { None }
+
+	pub fn connector_name(&self) -> Option<String> { None }
+
+	pub fn subpixel_layout(&self) -> monitor::SubpixelLayout { monitor::SubpixelLayout::Unknown }
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
@@ -10,7 +10,7 @@ use std::{
 
 use crate::{
   dpi::{PhysicalPosition, PhysicalSize},
-  monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
+  monitor::{MonitorHandle as RootMonitorHandle, SubpixelLayout, VideoMode as RootVideoMode},
   platform_impl::platform::{
     app_state,
     ffi::{id, nil, CGFloat, CGRect, CGSize, NSInteger, NSUInteger},
@@ -254,6 +254,26 @@ impl Inner {
 
     modes.into_iter()
   }
+
+  pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+    None
+  }
+
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    None
+  }
+
+  pub fn manufacturer(&self) -> Option<String> {
+    None
+  }
+
+  pub fn connector_name(&self) -> Option<String> {
+    None
+  }
+
+  pub fn subpixel_layout(&self) -> SubpixelLayout {
+    SubpixelLayout::Unknown
+  }
 }
 
 // MonitorHandleExtIOS
@@ -26,8 +26,8 @@ use crate::{
     monitor, set_badge_count, view, EventLoopWindowTarget, MonitorHandle,
   },
   window::{
-    CursorIcon, Fullscreen, ResizeDirection, Theme, UserAttentionType, WindowAttributes,
-    WindowId as RootWindowId, WindowSizeConstraints,
+    CursorIcon, DragActions, DragItem, Fullscreen, Icon, ResizeDirection, Theme,
+    UserAttentionType, WindowAttributes, WindowId as RootWindowId, WindowSizeConstraints,
   },
 };
 
@@ -231,6 +231,15 @@ impl Inner {
     Err(ExternalError::NotSupported(NotSupportedError::new()))
   }
 
+  pub fn start_drag(
+    &self,
+    _items: Vec<DragItem>,
+    _allowed_actions: DragActions,
+    _icon: Option<Icon>,
+  ) -> Result<(), ExternalError> {
+    Err(ExternalError::NotSupported(NotSupportedError::new()))
+  }
+
   pub fn set_ignore_cursor_events(&self, _ignore: bool) -> Result<(), ExternalError> {
     Err(ExternalError::NotSupported(NotSupportedError::new()))
   }
@@ -0,0 +1,151 @@
+use gtk::{
+	TargetEntry,
+	TargetFlags,
+	gdk::{self, Atom},
+	gdk_pixbuf::{Colorspace, Pixbuf},
+	prelude::*,
+};
+use url::Url;
+
+use crate::{
+	clipboard::{ClipboardImage, ClipboardKind},
+	error::ExternalError,
+	event::Event,
+	icon::PIXEL_SIZE,
+};
+
+const URI_LIST:&str = "text/uri-list";
+
+pub struct Clipboard {
+	clipboard:gtk::Clipboard,
+}
+
+impl Clipboard {
+	pub fn new(display:&gdk::Display, kind:ClipboardKind) -> Self {
+		Self { clipboard:gtk::Clipboard::for_display(display, &selection(kind)) }
+	}
+
+	pub fn set_text(&self, text:&str) -> Result<(), ExternalError> {
+		self.clipboard.set_text(text);
+
+		Ok(())
+	}
+
+	pub fn text(&self) -> Option<String> { self.clipboard.wait_for_text().map(Into::into) }
+
+	pub fn set_image(&self, image:&ClipboardImage) -> Result<(), ExternalError> {
+		let (width, height) = (image.width() as i32, image.height() as i32);
+
+		let pixbuf = Pixbuf::from_mut_slice(
+			image.rgba().to_vec(),
+			Colorspace::Rgb,
+			true,
+			8,
+			width,
+			height,
+			width * PIXEL_SIZE as i32,
+		);
+
+		self.clipboard.set_image(&pixbuf);
+
+		Ok(())
+	}
+
+	pub fn image(&self) -> Option<ClipboardImage> {
+		let pixbuf = self.clipboard.wait_for_image()?;
+
+		let pixbuf = if pixbuf.has_alpha() { pixbuf } else { pixbuf.add_alpha(false, 0, 0, 0)? };
+
+		if pixbuf.bits_per_sample() != 8 {
+			return None;
+		}
+
+		let (width, height) = (pixbuf.width() as usize, pixbuf.height() as usize);
+
+		let row_stride = pixbuf.rowstride() as usize;
+
+		let bytes = pixbuf.read_pixel_bytes();
+
+		// Rows may be padded, and the last one is only guaranteed to hold its pixels.
+		let mut rgba = Vec::with_capacity(width * height * PIXEL_SIZE);
+		for row in 0..height {
+			let start = row * row_stride;
+			rgba.extend_from_slice(bytes.get(start..start + width * PIXEL_SIZE)?);
+		}
+
+		ClipboardImage::from_rgba(rgba, width as u32, height as u32).ok()
+	}
+
+	pub fn set_uris(&self, uris:&[Url]) -> Result<(), ExternalError> {
+		let uris:Vec<String> = uris.iter().map(|uri| uri.to_string()).collect();
+
+		self.set_with_data(URI_LIST, move |selection_data| {
+			let uris:Vec<&str> = uris.iter().map(String::as_str).collect();
+			selection_data.set_uris(&uris);
+		})
+	}
+
+	pub fn uris(&self) -> Vec<Url> {
+		self.clipboard.wait_for_uris().iter().filter_map(|uri| Url::parse(uri).ok()).collect()
+	}
+
+	pub fn set_data(&self, mime_type:&str, data:Vec<u8>) -> Result<(), ExternalError> {
+		let target = Atom::intern(mime_type);
+
+		self.set_with_data(mime_type, move |selection_data| {
+			selection_data.set(&target, 8, &data);
+		})
+	}
+
+	pub fn data(&self, mime_type:&str) -> Option<Vec<u8>> {
+		let selection_data = self.clipboard.wait_for_contents(&Atom::intern(mime_type))?;
+
+		// A negative length means the owner couldn't convert the content.
+		(selection_data.length() >= 0).then(|| selection_data.data())
+	}
+
+	pub fn clear(&self) { self.clipboard.clear() }
+
+	fn set_with_data<F:Fn(&gtk::SelectionData) + 'static>(
+		&self,
+		target:&str,
+		f:F,
+	) -> Result<(), ExternalError> {
+		let targets = [TargetEntry::new(target, TargetFlags::empty(), 0)];
+
+		if self.clipboard.set_with_data(&targets, move |_, selection_data, _| f(selection_data)) {
+			Ok(())
+		} else {
+			Err(ExternalError::Os(os_error!(super::OsError)))
+		}
+	}
+}
+
+fn selection(kind:ClipboardKind) -> Atom {
+	match kind {
+		ClipboardKind::Primary => gdk::SELECTION_PRIMARY,
+		_ => gdk::SELECTION_CLIPBOARD,
+	}
+}
+
+/// Sends `Event::ClipboardChanged` whenever another owner takes over one of the
+/// clipboards.
+pub fn watch<T:'static>(
+	display:&gdk::Display,
+	event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+) {
+	for kind in [ClipboardKind::Clipboard, ClipboardKind::Primary] {
+		let clipboard = gtk::Clipboard::for_display(display, &selection(kind));
+
+		let event_tx = event_tx.clone();
+
+		// `owner-change` isn't bound by gtk-rs.
+		clipboard.connect_local("owner-change", false, move |_| {
+			if let Err(e) = event_tx.send(Event::ClipboardChanged(kind)) {
+				log::warn!("Failed to send clipboard changed event to event channel: {}", e);
+			}
+
+			None
+		});
+	}
+}
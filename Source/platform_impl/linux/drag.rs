@@ -0,0 +1,178 @@
+use std::{
+	cell::{Cell, RefCell},
+	rc::Rc,
+};
+
+use gtk::{
+	TargetList,
+	gdk::{self, Atom, DragContext},
+	gdk_pixbuf::Pixbuf,
+	glib::{self, SignalHandlerId},
+	prelude::*,
+};
+use url::Url;
+
+use super::window::WindowId;
+use crate::{
+	event::{Event, WindowEvent},
+	icon::Icon,
+	window::{DragAction, DragActions, DragItem, DragResult, WindowId as RootWindowId},
+};
+
+/// Starts dragging `items` out of `window` and reports the outcome with
+/// `WindowEvent::DragFinished`.
+pub fn start<T:'static>(
+	window:&gtk::Window,
+	id:WindowId,
+	items:Vec<DragItem>,
+	allowed_actions:DragActions,
+	icon:Option<Icon>,
+	event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+) {
+	let targets = TargetList::new(&[]);
+
+	for (info, item) in items.iter().enumerate() {
+		match item {
+			DragItem::Files(_) => targets.add_uri_targets(info as u32),
+			DragItem::Text(_) => targets.add_text_targets(info as u32),
+			DragItem::Data { mime_type, .. } => {
+				targets.add(&Atom::intern(mime_type), 0, info as u32)
+			},
+		}
+	}
+
+	// The handlers are connected for the duration of this drag only, and ignore any other drag
+	// the window may be the source of.
+	let context:Rc<RefCell<Option<DragContext>>> = Rc::default();
+
+	let failed = Rc::new(Cell::new(false));
+
+	let handlers:Rc<RefCell<Vec<SignalHandlerId>>> = Rc::default();
+
+	let context_ = context.clone();
+
+	let data_get = window.connect_drag_data_get(move |_, context, selection_data, info, _| {
+		if context_.borrow().as_ref() != Some(context) {
+			return;
+		}
+
+		match items.get(info as usize) {
+			Some(DragItem::Files(paths)) => {
+				let uris:Vec<String> = paths
+					.iter()
+					.filter_map(|path| Url::from_file_path(path).ok())
+					.map(String::from)
+					.collect();
+
+				let uris:Vec<&str> = uris.iter().map(String::as_str).collect();
+
+				selection_data.set_uris(&uris);
+			},
+			Some(DragItem::Text(text)) => {
+				selection_data.set_text(text);
+			},
+			Some(DragItem::Data { data, .. }) => {
+				selection_data.set(&selection_data.target(), 8, data);
+			},
+			None => {},
+		}
+	});
+
+	let context_ = context.clone();
+
+	let failed_ = failed.clone();
+
+	let drag_failed = window.connect_drag_failed(move |_, context, _| {
+		if context_.borrow().as_ref() == Some(context) {
+			failed_.set(true);
+		}
+
+		glib::Propagation::Proceed
+	});
+
+	let context_ = context.clone();
+
+	let handlers_ = handlers.clone();
+
+	let event_tx_ = event_tx.clone();
+
+	let drag_end = window.connect_drag_end(move |window, context| {
+		if context_.borrow().as_ref() != Some(context) {
+			return;
+		}
+
+		let result = if failed.get() {
+			DragResult::Cancelled
+		} else {
+			drag_result(context.selected_action())
+		};
+
+		send_result(&event_tx_, id, result);
+
+		context_.take();
+
+		for handler in handlers_.take() {
+			window.disconnect(handler);
+		}
+	});
+
+	handlers.borrow_mut().extend([data_get, drag_failed, drag_end]);
+
+	let actions = gdk_drag_actions(allowed_actions);
+
+	match window.drag_begin_with_coordinates(&targets, actions, 1, None, -1, -1) {
+		Some(drag_context) => {
+			if let Some(icon) = icon {
+				let pixbuf:Pixbuf = icon.inner.into();
+
+				drag_context.drag_set_icon_pixbuf(&pixbuf, pixbuf.width() / 2, pixbuf.height() / 2);
+			}
+
+			context.replace(Some(drag_context));
+		},
+		None => {
+			for handler in handlers.take() {
+				window.disconnect(handler);
+			}
+
+			send_result(event_tx, id, DragResult::Cancelled);
+		},
+	}
+}
+
+fn send_result<T>(
+	event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+	id:WindowId,
+	result:DragResult,
+) {
+	if let Err(e) = event_tx.send(Event::WindowEvent {
+		window_id:RootWindowId(id),
+		event:WindowEvent::DragFinished(result),
+	}) {
+		log::warn!("Failed to send drag finished event to event channel: {}", e);
+	}
+}
+
+fn gdk_drag_actions(actions:DragActions) -> gdk::DragAction {
+	let mut gdk_actions = gdk::DragAction::empty();
+
+	gdk_actions.set(gdk::DragAction::COPY, actions.contains(DragActions::COPY));
+
+	gdk_actions.set(gdk::DragAction::MOVE, actions.contains(DragActions::MOVE));
+
+	gdk_actions.set(gdk::DragAction::LINK, actions.contains(DragActions::LINK));
+
+	gdk_actions
+}
+
+fn drag_result(action:gdk::DragAction) -> DragResult {
+	if action.contains(gdk::DragAction::MOVE) {
+		DragResult::Dropped(DragAction::Move)
+	} else if action.contains(gdk::DragAction::LINK) {
+		DragResult::Dropped(DragAction::Link)
+	} else if action.contains(gdk::DragAction::COPY) {
+		DragResult::Dropped(DragAction::Copy)
+	} else {
+		DragResult::Cancelled
+	}
+}
@@ -3,18 +3,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-  cell::RefCell,
+  cell::{Cell, RefCell},
   collections::{HashSet, VecDeque},
   error::Error,
   process,
   rc::Rc,
   sync::atomic::{AtomicBool, Ordering},
-  time::Instant,
+  time::{Duration, Instant},
 };
 
 use cairo::{RectangleInt, Region};
 use crossbeam_channel::SendError;
-use gdk::{Cursor, CursorType, EventKey, EventMask, ScrollDirection, WindowEdge, WindowState};
+use gdk::{
+  Cursor, CursorType, EventKey, EventMask, EventType, EventVisibility, ScrollDirection,
+  VisibilityState, WindowEdge, WindowState,
+};
 use gio::Cancellable;
 use glib::{source::Priority, MainContext};
 use gtk::{
@@ -25,23 +28,30 @@ use gtk::{
 };
 
 use crate::{
+  clipboard::ClipboardKind,
   dpi::{LogicalPosition, LogicalSize, PhysicalPosition},
   error::ExternalError,
   event::{
     ElementState, Event, MouseButton, MouseScrollDelta, StartCause, TouchPhase, WindowEvent,
   },
-  event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW},
+  event_loop::{ControlFlow, EventLoopClosed, EventLoopWindowTarget as RootELW, InhibitFlags},
   keyboard::ModifiersState,
   monitor::MonitorHandle as RootMonitorHandle,
+  notification::{Notification, NotificationId},
   platform_impl::platform::{device, DEVICE_ID},
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme, WindowId as RootWindowId,
+    CursorIcon, Fullscreen, HitTestResult, ProgressBarState, ResizeDirection, Theme,
+    WindowId as RootWindowId,
   },
 };
 
 use super::{
-  keyboard,
+  clipboard::{self, Clipboard},
+  drag, keyboard,
   monitor::{self, MonitorHandle},
+  notification::Notifier,
+  power,
+  session::{self, InhibitCookie},
   taskbar, util,
   window::{WindowId, WindowRequest},
 };
@@ -60,6 +70,10 @@ pub struct EventLoopWindowTarget<T> {
   pub(crate) window_requests_tx: glib::Sender<(WindowId, WindowRequest)>,
   /// Draw event sender
   pub(crate) draw_tx: crossbeam_channel::Sender<WindowId>,
+  /// Activation token handed to the process at startup, consumed by the first window
+  pub(crate) startup_activation_token: Rc<RefCell<Option<String>>>,
+  /// Notifications shown by the application
+  pub(crate) notifier: Rc<Notifier>,
   _marker: std::marker::PhantomData<T>,
 }
 
@@ -145,6 +159,59 @@ impl<T> EventLoopWindowTarget<T> {
     self.display.backend().is_x11()
   }
 
+  /// Obtains an activation token to hand to a process launched by the application, so that it
+  /// may focus its first window.
+  pub fn request_activation_token(&self) -> Result<String, ExternalError> {
+    let context = self
+      .display
+      .app_launch_context()
+      .ok_or_else(|| ExternalError::Os(os_error!(super::OsError)))?;
+    context.set_timestamp(gtk::current_event_time());
+
+    // The application info only describes the launched program to X11 startup notification.
+    let program = glib::prgname().unwrap_or_else(|| "tao".into());
+    let app_info = gio::AppInfo::create_from_commandline(
+      program.as_str(),
+      None,
+      gio::AppInfoCreateFlags::SUPPORTS_STARTUP_NOTIFICATION,
+    )
+    .map_err(|_| ExternalError::Os(os_error!(super::OsError)))?;
+
+    context
+      .startup_notify_id(&app_info, &[])
+      .map(Into::into)
+      .ok_or_else(|| ExternalError::Os(os_error!(super::OsError)))
+  }
+
+  #[inline]
+  pub fn user_idle_time(&self) -> Result<Duration, ExternalError> {
+    session::user_idle_time(&self.display)
+  }
+
+  #[inline]
+  pub fn inhibit(&self, flags: InhibitFlags, reason: &str) -> Result<InhibitCookie, ExternalError> {
+    session::inhibit(&self.app, flags, reason)
+  }
+
+  #[inline]
+  pub fn show_notification(
+    &self,
+    notification: &Notification,
+    replaces: Option<NotificationId>,
+  ) -> Result<NotificationId, ExternalError> {
+    self.notifier.show(notification, replaces)
+  }
+
+  #[inline]
+  pub fn close_notification(&self, id: NotificationId) -> Result<(), ExternalError> {
+    self.notifier.close(id)
+  }
+
+  #[inline]
+  pub fn clipboard(&self, kind: ClipboardKind) -> Clipboard {
+    Clipboard::new(&self.display, kind)
+  }
+
   #[inline]
   pub fn cursor_position(&self) -> Result<PhysicalPosition<f64>, ExternalError> {
     util::cursor_position(self.is_wayland())
@@ -215,10 +282,24 @@ impl<T: 'static> EventLoop<T> {
   }
 
   fn new_gtk(app_id: Option<&str>) -> Result<EventLoop<T>, Box<dyn Error>> {
+    // Take the activation token we were launched with before GDK sees it, and so that it isn't
+    // inherited by our own child processes. It is handed to the first window.
+    let tokens = ["XDG_ACTIVATION_TOKEN", "DESKTOP_STARTUP_ID"].map(|var| {
+      let token = std::env::var(var).ok();
+      std::env::remove_var(var);
+      token
+    });
+    let startup_activation_token = tokens
+      .into_iter()
+      .flatten()
+      .find(|token| !token.is_empty());
+
     // This should be done by gtk::Application::new, but does not work properly
     gtk::init()?;
     let context = MainContext::default();
     let app = gtk::Application::new(app_id, gio::ApplicationFlags::empty());
+    // Register with the session manager to be notified when the session ends.
+    app.set_register_session(true);
     let app_ = app.clone();
     let cancellable: Option<&Cancellable> = None;
     app.register(cancellable)?;
@@ -245,6 +326,8 @@ impl<T: 'static> EventLoop<T> {
       windows: Rc::new(RefCell::new(HashSet::new())),
       window_requests_tx,
       draw_tx: draw_tx_,
+      startup_activation_token: Rc::new(RefCell::new(startup_activation_token)),
+      notifier: Rc::new(Notifier::new(&event_tx)),
       _marker: std::marker::PhantomData,
     };
 
@@ -273,7 +356,83 @@ impl<T: 'static> EventLoop<T> {
       None
     };
 
-    let mut taskbar = TaskbarIndicator::new();
+    // Spawn evdev thread to receive game controller events, on both x11 and wayland.
+    #[cfg(target_os = "linux")]
+    {
+      let (gamepad_tx, gamepad_rx) = glib::MainContext::channel(glib::Priority::default());
+      let user_event_tx = user_event_tx.clone();
+      super::gamepad::spawn(gamepad_tx);
+      gamepad_rx.attach(Some(&context), move |(device_id, event)| {
+        if let Err(e) = user_event_tx.send(Event::DeviceEvent {
+          device_id: crate::event::DeviceId(device_id),
+          event,
+        }) {
+          log::warn!("Fail to send gamepad event to event channel: {}", e);
+        }
+        glib::ControlFlow::Continue
+      });
+    }
+
+    // Report monitor hotplug and configuration changes.
+    let display = &window_target.display;
+    for i in 0..display.n_monitors() {
+      if let Some(monitor) = display.monitor(i) {
+        monitor::watch(&monitor, &event_tx);
+      }
+    }
+
+    let tx_clone = event_tx.clone();
+    display.connect_monitor_added(move |_, monitor| {
+      monitor::watch(monitor, &tx_clone);
+      monitor::send_event(&tx_clone, Event::MonitorAdded(monitor.into()));
+    });
+
+    let tx_clone = event_tx.clone();
+    let app_clone = app_.clone();
+    display.connect_monitor_removed(move |display, monitor| {
+      // Wayland doesn't let clients position their windows, the compositor relocates them.
+      if display.backend().is_x11() {
+        monitor::relocate_windows(&app_clone, display, monitor);
+      }
+      monitor::send_event(&tx_clone, Event::MonitorRemoved(monitor.into()));
+    });
+
+    // GDK has no notification for the primary monitor changing, so we compare it on every
+    // `monitors-changed` signal of the screen.
+    let primary_monitor = Rc::new(RefCell::new(display.primary_monitor()));
+    let tx_clone = event_tx.clone();
+    display
+      .default_screen()
+      .connect_monitors_changed(move |screen| {
+        let display = screen.display();
+        let new_primary = display.primary_monitor();
+        let old_primary = primary_monitor.replace(new_primary.clone());
+        if old_primary == new_primary {
+          return;
+        }
+
+        let connected: Vec<gdk::Monitor> = (0..display.n_monitors())
+          .filter_map(|i| display.monitor(i))
+          .collect();
+        for monitor in [old_primary, new_primary]
+          .into_iter()
+          .flatten()
+          .filter(|m| connected.contains(m))
+        {
+          monitor::send_event(&tx_clone, Event::MonitorChanged((&monitor).into()));
+        }
+      });
+
+    // Report clipboard owner changes.
+    clipboard::watch(display, &event_tx);
+
+    // Report the end of the session.
+    session::watch(&window_target.app, &event_tx);
+
+    // Report sleep and power source changes.
+    power::watch(&event_tx);
+
+    let mut taskbar = TaskbarIndicator::new(app_id);
     let is_wayland = window_target.is_wayland();
 
     // Window Request
@@ -296,8 +455,22 @@ impl<T: 'static> EventLoop<T> {
           WindowRequest::Focus => {
             window.present_with_time(gdk::ffi::GDK_CURRENT_TIME as _);
           }
+          WindowRequest::FocusWithToken(token) => {
+            // GTK only stores a real startup id on a mapped window, so it is presented with the
+            // time the token was created at, or the current time if it doesn't carry one.
+            window.set_startup_id(&token);
+            let time = token
+              .rsplit_once("_TIME")
+              .and_then(|(_, time)| time.parse::<u32>().ok())
+              .unwrap_or(gdk::ffi::GDK_CURRENT_TIME as _);
+            window.present_with_time(time);
+          }
           WindowRequest::Resizable(resizable) => window.set_resizable(resizable),
           WindowRequest::Closable(closable) => window.set_deletable(closable),
+          WindowRequest::Functions {
+            minimizable,
+            maximizable,
+          } => util::set_functions(&window, minimizable, maximizable),
           WindowRequest::Minimized(minimized) => {
             if minimized {
               window.iconify();
@@ -342,6 +515,11 @@ impl<T: 'static> EventLoop<T> {
               );
             }
           }
+          WindowRequest::StartDrag {
+            items,
+            allowed_actions,
+            icon,
+          } => drag::start(&window, id, items, allowed_actions, icon, &event_tx),
           WindowRequest::Fullscreen(fullscreen) => match fullscreen {
             Some(f) => {
               if let Fullscreen::Borderless(m) = f {
@@ -443,6 +621,39 @@ impl<T: 'static> EventLoop<T> {
               window.input_shape_combine_region(None)
             };
           }
+          WindowRequest::Opacity(opacity) => window.set_opacity(opacity),
+          WindowRequest::InputRegion { region, shape } => {
+            // On X11 the server already limits the input region to the shape.
+            let shape = shape.filter(|_| is_wayland);
+            let regions: Option<Vec<_>> = region
+              .iter()
+              .chain(shape.iter())
+              .map(|region| util::cairo_region(region, window.scale_factor()))
+              .collect();
+            match regions.as_deref() {
+              Some([]) => window.input_shape_combine_region(None),
+              Some([region, others @ ..]) => {
+                for other in others {
+                  let _ = region.intersect(other);
+                }
+                window.input_shape_combine_region(Some(region));
+              }
+              None => log::warn!("Failed to create the input region of the window"),
+            }
+          }
+          // Wayland has no window shapes, the shape is applied as part of the input region.
+          WindowRequest::Shape(_) if is_wayland => {}
+          WindowRequest::Shape(shape) => {
+            match shape.map(|shape| util::cairo_region(&shape, window.scale_factor())) {
+              Some(Some(shape)) => window.shape_combine_region(Some(&shape)),
+              Some(None) => log::warn!("Failed to create the shape of the window"),
+              None => window.shape_combine_region(None),
+            }
+          }
+          #[cfg(feature = "layer-shell")]
+          WindowRequest::LayerShell(request) => {
+            super::wayland::layer_shell::apply(&window, request)
+          }
           WindowRequest::ProgressBarState(_) => unreachable!(),
           WindowRequest::BadgeCount(_, _) => unreachable!(),
           WindowRequest::SetTheme(_) => unreachable!(),
@@ -450,6 +661,7 @@ impl<T: 'static> EventLoop<T> {
             transparent,
             fullscreen,
             cursor_moved,
+            hit_tester,
           } => {
             window.add_events(
               EventMask::POINTER_MOTION_MASK
@@ -458,6 +670,7 @@ impl<T: 'static> EventLoop<T> {
                 | EventMask::TOUCH_MASK
                 | EventMask::STRUCTURE_MASK
                 | EventMask::FOCUS_CHANGE_MASK
+                | EventMask::VISIBILITY_NOTIFY_MASK
                 | EventMask::SCROLL_MASK,
             );
 
@@ -474,103 +687,109 @@ impl<T: 'static> EventLoop<T> {
               glib::Propagation::Proceed
             });
 
-            // Allow resizing unmaximized non-fullscreen undecorated window
+            // Resizing is allowed for unmaximized non-fullscreen undecorated windows, and the hit
+            // test lets the application draw its own caption.
             let fullscreen_ = fullscreen.clone();
+            let hit_tester_ = hit_tester.clone();
             window.connect_motion_notify_event(move |window, event| {
-              if !window.is_decorated() && window.is_resizable() && !window.is_maximized() {
-                if let Some(window) = window.window() {
-                  let (cx, cy) = event.root();
-                  let (left, top) = window.position();
-                  let (w, h) = (window.width(), window.height());
-                  let (right, bottom) = (left + w, top + h);
-                  let border = window.scale_factor() * 5;
-                  let edge = crate::window::hit_test(
-                    (left, top, right, bottom),
-                    cx as _,
-                    cy as _,
-                    border,
-                    border,
-                  );
+              if hit_tester_.take_caption_drag(window, event.position()) {
+                let (x, y) = event.root();
+                window.begin_move_drag(1, x as i32, y as i32, event.time());
+                return glib::Propagation::Proceed;
+              }
 
-                  let edge = match &edge {
-                    Some(e) if !fullscreen_.load(Ordering::Relaxed) => e.to_cursor_str(),
+              if !window.is_decorated() && window.is_resizable() && !window.is_maximized() {
+                if let Some(gdk_window) = window.window() {
+                  let fullscreen = fullscreen_.load(Ordering::Relaxed);
+                  let cursor = match hit_tester_.test(window, event.root(), !fullscreen) {
+                    HitTestResult::Resize(direction) => direction.to_cursor_str(),
                     _ => "default",
                   };
-                  window.set_cursor(Cursor::from_name(&window.display(), edge).as_ref());
+                  gdk_window.set_cursor(Cursor::from_name(&gdk_window.display(), cursor).as_ref());
                 }
               }
               glib::Propagation::Proceed
             });
+            let fullscreen_ = fullscreen.clone();
+            let hit_tester_ = hit_tester.clone();
             window.connect_button_press_event(move |window, event| {
               const LMB: u32 = 1;
-              if (is_wayland || !window.is_decorated())
+              if event.button() != LMB {
+                return glib::Propagation::Proceed;
+              }
+
+              let resizable = (is_wayland || !window.is_decorated())
                 && window.is_resizable()
                 && !window.is_maximized()
-                && event.button() == LMB
-              {
-                let (cx, cy) = event.root();
-                let (left, top) = window.position();
-                let (w, h) = window.size();
-                let (right, bottom) = (left + w, top + h);
-                let border = window.scale_factor() * 5;
-                let edge = crate::window::hit_test(
-                  (left, top, right, bottom),
-                  cx as _,
-                  cy as _,
-                  border,
-                  border,
-                )
-                .map(|d| d.to_gtk_edge())
-                // we return `WindowEdge::__Unknown` to be ignored later.
-                // we must return 8 or bigger, otherwise it will be the same as one of the other 7 variants of `WindowEdge` enum.
-                .unwrap_or(WindowEdge::__Unknown(8));
-                // Ignore the `__Unknown` variant so the window receives the click correctly if it is not on the edges.
-                match edge {
-                  WindowEdge::__Unknown(_) => (),
-                  _ => {
-                    // FIXME: calling `window.begin_resize_drag` uses the default cursor, it should show a resizing cursor instead
-                    window.begin_resize_drag(edge, LMB as i32, cx as i32, cy as i32, event.time())
+                && !fullscreen_.load(Ordering::Relaxed);
+              let (cx, cy) = event.root();
+              match hit_tester_.test(window, (cx, cy), resizable) {
+                HitTestResult::Resize(direction) => {
+                  // The window manager keeps the cursor it had when the drag started.
+                  if let Some(gdk_window) = window.window() {
+                    gdk_window.set_cursor(
+                      Cursor::from_name(&gdk_window.display(), direction.to_cursor_str()).as_ref(),
+                    );
+                  }
+                  window.begin_resize_drag(
+                    direction.to_gtk_edge(),
+                    LMB as i32,
+                    cx as i32,
+                    cy as i32,
+                    event.time(),
+                  )
+                }
+                HitTestResult::Caption if event.event_type() == EventType::DoubleButtonPress => {
+                  hit_tester_.release_caption();
+                  if window.is_resizable() {
+                    if window.is_maximized() {
+                      window.unmaximize();
+                    } else {
+                      window.maximize();
+                    }
                   }
                 }
+                // The window is only moved once the pointer is dragged, so that the second press
+                // of a double-click is still received.
+                HitTestResult::Caption => hit_tester_.press_caption(event.position()),
+                _ => (),
               }
 
               glib::Propagation::Proceed
             });
+            let hit_tester_ = hit_tester.clone();
+            window.connect_button_release_event(move |_, _| {
+              hit_tester_.release_caption();
+              glib::Propagation::Proceed
+            });
+            let hit_tester_ = hit_tester.clone();
             window.connect_touch_event(move |window, event| {
-              if !window.is_decorated() && window.is_resizable() && !window.is_maximized() {
-                if let Some(window) = window.window() {
-                  if let Some((cx, cy)) = event.root_coords() {
-                    if let Some(device) = event.device() {
-                      let (left, top) = window.position();
-                      let (w, h) = (window.width(), window.height());
-                      let (right, bottom) = (left + w, top + h);
-                      let border = window.scale_factor() * 5;
-                      let edge = crate::window::hit_test(
-                        (left, top, right, bottom),
-                        cx as _,
-                        cy as _,
-                        border,
-                        border,
-                      )
-                      .map(|d| d.to_gtk_edge())
-                      // we return `WindowEdge::__Unknown` to be ignored later.
-                      // we must return 8 or bigger, otherwise it will be the same as one of the other 7 variants of `WindowEdge` enum.
-                      .unwrap_or(WindowEdge::__Unknown(8));
-
-                      // Ignore the `__Unknown` variant so the window receives the click correctly if it is not on the edges.
-                      match edge {
-                        WindowEdge::__Unknown(_) => (),
-                        _ => window.begin_resize_drag_for_device(
-                          edge,
-                          &device,
-                          0,
-                          cx as i32,
-                          cy as i32,
-                          event.time(),
-                        ),
-                      }
-                    }
-                  }
+              if event.event_type() != EventType::TouchBegin {
+                return glib::Propagation::Proceed;
+              }
+
+              if let (Some(gdk_window), Some((cx, cy)), Some(device)) =
+                (window.window(), event.root_coords(), event.device())
+              {
+                let resizable =
+                  !window.is_decorated() && window.is_resizable() && !window.is_maximized();
+                match hit_tester_.test(window, (cx, cy), resizable) {
+                  HitTestResult::Resize(direction) => gdk_window.begin_resize_drag_for_device(
+                    direction.to_gtk_edge(),
+                    &device,
+                    0,
+                    cx as i32,
+                    cy as i32,
+                    event.time(),
+                  ),
+                  HitTestResult::Caption => gdk_window.begin_move_drag_for_device(
+                    &device,
+                    0,
+                    cx as i32,
+                    cy as i32,
+                    event.time(),
+                  ),
+                  _ => (),
                 }
               }
 
@@ -887,6 +1106,74 @@ impl<T: 'static> EventLoop<T> {
                   );
                 }
               }
+
+              let reported = WindowState::MAXIMIZED
+                | WindowState::ICONIFIED
+                | WindowState::FULLSCREEN
+                | WindowState::FOCUSED
+                | WindowState::TILED
+                | WindowState::TOP_TILED
+                | WindowState::RIGHT_TILED
+                | WindowState::BOTTOM_TILED
+                | WindowState::LEFT_TILED;
+              if state.intersects(reported) {
+                if let Err(e) = tx_clone.send(Event::WindowEvent {
+                  window_id: RootWindowId(id),
+                  event: WindowEvent::StateChanged(util::window_state_flags(
+                    event.new_window_state(),
+                  )),
+                }) {
+                  log::warn!(
+                    "Failed to send window state changed event to event channel: {}",
+                    e
+                  );
+                }
+              }
+              glib::Propagation::Proceed
+            });
+
+            // The window is occluded when the window manager hides it (minimized, on another
+            // workspace) or, without a compositor on X11, when other windows fully cover it.
+            let hidden = Rc::new(Cell::new(false));
+            let obscured = Rc::new(Cell::new(false));
+            let send_occluded = {
+              let tx_clone = event_tx.clone();
+              let (hidden, obscured) = (hidden.clone(), obscured.clone());
+              let occluded = Cell::new(false);
+              Rc::new(move || {
+                let is_occluded = hidden.get() || obscured.get();
+                if occluded.replace(is_occluded) != is_occluded {
+                  if let Err(e) = tx_clone.send(Event::WindowEvent {
+                    window_id: RootWindowId(id),
+                    event: WindowEvent::Occluded(is_occluded),
+                  }) {
+                    log::warn!(
+                      "Failed to send window occluded event to event channel: {}",
+                      e
+                    );
+                  }
+                }
+              })
+            };
+            let (obscured_, send_occluded_) = (obscured.clone(), send_occluded.clone());
+            window.connect_window_state_event(move |_, event| {
+              let state = event.new_window_state();
+              hidden.set(state.intersects(WindowState::WITHDRAWN | WindowState::ICONIFIED));
+              // Compositors never send visibility notifications, so a window that gets focused
+              // is assumed to have been raised above whatever covered it.
+              if state.contains(WindowState::FOCUSED) {
+                obscured_.set(false);
+              }
+              send_occluded_();
+              glib::Propagation::Proceed
+            });
+            window.connect_event(move |_, event| {
+              if event.event_type() == EventType::VisibilityNotify {
+                if let Some(event) = event.downcast_ref::<EventVisibility>() {
+                  obscured.set(event.state() == VisibilityState::FullyObscured);
+                  send_occluded();
+                }
+              }
               glib::Propagation::Proceed
             });
 
@@ -0,0 +1,514 @@
+//! Game controller input read from the evdev devices in `/dev/input`.
+
+use std::{
+	collections::HashMap,
+	ffi::OsStr,
+	fs::{self, File, OpenOptions},
+	io,
+	mem,
+	os::{
+		fd::AsRawFd,
+		raw::{c_int, c_ulong, c_void},
+		unix::{ffi::OsStrExt, fs::OpenOptionsExt},
+	},
+	path::{Path, PathBuf},
+	ptr,
+	sync::{
+		Mutex,
+		atomic::{AtomicUsize, Ordering},
+	},
+};
+
+use gtk::glib;
+
+use super::DeviceId;
+use crate::event::{AxisId, ButtonId, DeviceEvent, ElementState, gamepad};
+
+const INPUT_DIR:&str = "/dev/input";
+
+// Event types and codes, see `linux/input-event-codes.h`.
+const EV_KEY:u16 = 0x01;
+const EV_ABS:u16 = 0x03;
+const KEY_MAX:u16 = 0x2ff;
+const ABS_MAX:u16 = 0x3f;
+
+const BTN_JOYSTICK:u16 = 0x120;
+const BTN_GAMEPAD:u16 = 0x130;
+
+const ABS_HAT0X:u16 = 0x10;
+const ABS_HAT0Y:u16 = 0x11;
+
+/// Metadata of a connected game controller.
+#[derive(Debug, Clone)]
+pub struct DeviceInfo {
+	pub name:String,
+	pub vendor_id:u16,
+	pub product_id:u16,
+}
+
+lazy_static! {
+	// Entries are kept after the device is removed so that `DeviceEvent::Removed` handlers can
+	// still query them. Ids are never reused.
+	static ref DEVICES: Mutex<HashMap<DeviceId, DeviceInfo>> = Default::default();
+}
+
+// `0` is the id shared by every device that isn't a game controller.
+static NEXT_DEVICE_ID:AtomicUsize = AtomicUsize::new(1);
+
+pub fn device_info(device_id:DeviceId) -> Option<DeviceInfo> {
+	DEVICES.lock().unwrap().get(&device_id).cloned()
+}
+
+/// Spawn the game controller thread. It reports the controllers that are
+/// connected at startup and then follows hotplug through inotify.
+pub fn spawn(device_tx:glib::Sender<(DeviceId, DeviceEvent)>) {
+	std::thread::spawn(move || {
+		let send = |device_id:DeviceId, event:DeviceEvent| {
+			if let Err(e) = device_tx.send((device_id, event)) {
+				log::info!(
+					"Failed to send gamepad event {} since receiver is closed. Closing gamepad \
+					 thread along with it",
+					e
+				);
+				false
+			} else {
+				true
+			}
+		};
+
+		let inotify = Inotify::new();
+
+		if inotify.is_none() {
+			log::warn!("Failed to watch {}, gamepad hotplug will not be reported", INPUT_DIR);
+		}
+
+		let mut gamepads = Vec::new();
+
+		if let Ok(entries) = fs::read_dir(INPUT_DIR) {
+			for entry in entries.flatten() {
+				if let Some(gamepad) = Gamepad::open(&entry.path()) {
+					if !send(gamepad.id, DeviceEvent::Added) {
+						return;
+					}
+					gamepads.push(gamepad);
+				}
+			}
+		}
+
+		loop {
+			let mut fds:Vec<libc::pollfd> = gamepads
+				.iter()
+				.map(|g| libc::pollfd { fd:g.file.as_raw_fd(), events:libc::POLLIN, revents:0 })
+				.collect();
+			if let Some(inotify) = &inotify {
+				fds.push(libc::pollfd { fd:inotify.fd, events:libc::POLLIN, revents:0 });
+			}
+
+			if unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as _, -1) } < 0 {
+				let error = io::Error::last_os_error();
+				if error.kind() == io::ErrorKind::Interrupted {
+					continue;
+				}
+				log::warn!("Failed to poll gamepad devices: {}", error);
+				return;
+			}
+
+			// Read input before handling hotplug, `fds` matches `gamepads` until then.
+			let mut removed = Vec::new();
+			for (i, gamepad) in gamepads.iter_mut().enumerate() {
+				if fds[i].revents == 0 {
+					continue;
+				}
+
+				match gamepad.read_events() {
+					Ok(events) => {
+						for event in events {
+							if !send(gamepad.id, event) {
+								return;
+							}
+						}
+					},
+					Err(_) => removed.push(gamepad.path.clone()),
+				}
+			}
+
+			if let Some(inotify) = &inotify {
+				for (path, created) in inotify.read_events() {
+					if created {
+						if gamepads.iter().all(|g| g.path != path) {
+							if let Some(gamepad) = Gamepad::open(&path) {
+								if !send(gamepad.id, DeviceEvent::Added) {
+									return;
+								}
+								gamepads.push(gamepad);
+							}
+						}
+					} else {
+						removed.push(path);
+					}
+				}
+			}
+
+			for path in removed {
+				if let Some(i) = gamepads.iter().position(|g| g.path == path) {
+					let gamepad = gamepads.swap_remove(i);
+					if !send(gamepad.id, DeviceEvent::Removed) {
+						return;
+					}
+				}
+			}
+		}
+	});
+}
+
+struct Gamepad {
+	id:DeviceId,
+	path:PathBuf,
+	file:File,
+	axes:HashMap<u16, libc::input_absinfo>,
+	hat:(i32, i32),
+}
+
+impl Gamepad {
+	/// Opens the evdev device at `path` if it is a game controller.
+	fn open(path:&Path) -> Option<Self> {
+		if !path.file_name()?.as_bytes().starts_with(b"event") {
+			return None;
+		}
+
+		let file = OpenOptions::new()
+			.read(true)
+			.custom_flags(libc::O_NONBLOCK | libc::O_CLOEXEC)
+			.open(path)
+			.ok()?;
+		let fd = file.as_raw_fd();
+
+		let mut keys = [0u8; KEY_MAX as usize / 8 + 1];
+		if unsafe { libc::ioctl(fd, eviocgbit(EV_KEY, keys.len()) as _, keys.as_mut_ptr()) } < 0 {
+			return None;
+		}
+		if !test_bit(&keys, BTN_GAMEPAD) && !test_bit(&keys, BTN_JOYSTICK) {
+			return None;
+		}
+
+		let mut name = [0u8; 256];
+		let name = if unsafe { libc::ioctl(fd, eviocgname(name.len()) as _, name.as_mut_ptr()) } >= 0
+		{
+			let len = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+			String::from_utf8_lossy(&name[..len]).into_owned()
+		} else {
+			String::new()
+		};
+
+		let mut input_id:libc::input_id = unsafe { mem::zeroed() };
+		unsafe { libc::ioctl(fd, eviocgid() as _, &mut input_id) };
+
+		let mut axes = HashMap::new();
+		let mut abs = [0u8; ABS_MAX as usize / 8 + 1];
+		if unsafe { libc::ioctl(fd, eviocgbit(EV_ABS, abs.len()) as _, abs.as_mut_ptr()) } >= 0 {
+			for code in (0..=ABS_MAX).filter(|code| test_bit(&abs, *code)) {
+				let mut info:libc::input_absinfo = unsafe { mem::zeroed() };
+				if unsafe { libc::ioctl(fd, eviocgabs(code) as _, &mut info) } >= 0 {
+					axes.insert(code, info);
+				}
+			}
+		}
+
+		let id = DeviceId(NEXT_DEVICE_ID.fetch_add(1, Ordering::Relaxed));
+		DEVICES.lock().unwrap().insert(
+			id,
+			DeviceInfo { name, vendor_id:input_id.vendor, product_id:input_id.product },
+		);
+
+		Some(Self { id, path:path.to_owned(), file, axes, hat:(0, 0) })
+	}
+
+	/// Reads every pending input event. Fails once the device is unplugged.
+	fn read_events(&mut self) -> io::Result<Vec<DeviceEvent>> {
+		let mut events = Vec::new();
+
+		loop {
+			let mut event:libc::input_event = unsafe { mem::zeroed() };
+			let size = mem::size_of::<libc::input_event>();
+			let read = unsafe {
+				libc::read(self.file.as_raw_fd(), &mut event as *mut _ as *mut c_void, size)
+			};
+
+			if read < 0 {
+				let error = io::Error::last_os_error();
+				return match error.kind() {
+					io::ErrorKind::WouldBlock => Ok(events),
+					io::ErrorKind::Interrupted => continue,
+					_ => Err(error),
+				};
+			}
+			if read as usize != size {
+				return Ok(events);
+			}
+
+			match event.type_ {
+				// A value of 2 is a key repeat.
+				EV_KEY if event.value != 2 => {
+					let state =
+						if event.value == 1 { ElementState::Pressed } else { ElementState::Released };
+					events.push(DeviceEvent::Button { button:map_button(event.code), state });
+				},
+				EV_ABS if event.code == ABS_HAT0X => {
+					let (x, y) = self.hat;
+					hat_events(
+						&mut events,
+						x,
+						event.value,
+						gamepad::BUTTON_DPAD_LEFT,
+						gamepad::BUTTON_DPAD_RIGHT,
+					);
+					self.hat = (event.value, y);
+				},
+				EV_ABS if event.code == ABS_HAT0Y => {
+					let (x, y) = self.hat;
+					hat_events(
+						&mut events,
+						y,
+						event.value,
+						gamepad::BUTTON_DPAD_UP,
+						gamepad::BUTTON_DPAD_DOWN,
+					);
+					self.hat = (x, event.value);
+				},
+				EV_ABS => {
+					if let Some(info) = self.axes.get(&event.code) {
+						let (axis, is_trigger) = map_axis(event.code);
+						if let Some(value) = normalize(info, event.value, is_trigger) {
+							events.push(DeviceEvent::Motion { axis, value });
+						}
+					}
+				},
+				_ => {},
+			}
+		}
+	}
+}
+
+fn map_button(code:u16) -> ButtonId {
+	match code {
+		0x130 => gamepad::BUTTON_SOUTH,
+		0x131 => gamepad::BUTTON_EAST,
+		0x133 => gamepad::BUTTON_NORTH,
+		0x134 => gamepad::BUTTON_WEST,
+		0x136 => gamepad::BUTTON_LEFT_BUMPER,
+		0x137 => gamepad::BUTTON_RIGHT_BUMPER,
+		0x138 => gamepad::BUTTON_LEFT_TRIGGER,
+		0x139 => gamepad::BUTTON_RIGHT_TRIGGER,
+		0x13a => gamepad::BUTTON_SELECT,
+		0x13b => gamepad::BUTTON_START,
+		0x13c => gamepad::BUTTON_MODE,
+		0x13d => gamepad::BUTTON_LEFT_STICK,
+		0x13e => gamepad::BUTTON_RIGHT_STICK,
+		0x220 => gamepad::BUTTON_DPAD_UP,
+		0x221 => gamepad::BUTTON_DPAD_DOWN,
+		0x222 => gamepad::BUTTON_DPAD_LEFT,
+		0x223 => gamepad::BUTTON_DPAD_RIGHT,
+		code => gamepad::OTHER + code as ButtonId,
+	}
+}
+
+/// Returns the standard axis for an absolute axis code and whether it is a trigger.
+fn map_axis(code:u16) -> (AxisId, bool) {
+	match code {
+		0x00 => (gamepad::AXIS_LEFT_STICK_X, false),
+		0x01 => (gamepad::AXIS_LEFT_STICK_Y, false),
+		0x03 => (gamepad::AXIS_RIGHT_STICK_X, false),
+		0x04 => (gamepad::AXIS_RIGHT_STICK_Y, false),
+		// `ABS_Z` / `ABS_RZ` on most controllers, `ABS_BRAKE` / `ABS_GAS` on some others.
+		0x02 | 0x0a => (gamepad::AXIS_LEFT_TRIGGER, true),
+		0x05 | 0x09 => (gamepad::AXIS_RIGHT_TRIGGER, true),
+		code => (gamepad::OTHER + code as AxisId, false),
+	}
+}
+
+fn normalize(info:&libc::input_absinfo, value:i32, is_trigger:bool) -> Option<f64> {
+	let range = info.maximum as f64 - info.minimum as f64;
+	if range <= 0.0 {
+		return None;
+	}
+
+	let value = ((value as f64 - info.minimum as f64) / range).clamp(0.0, 1.0);
+	Some(if is_trigger { value } else { value * 2.0 - 1.0 })
+}
+
+/// Translates a change of a hat axis into directional pad button events.
+fn hat_events(
+	events:&mut Vec<DeviceEvent>,
+	old:i32,
+	new:i32,
+	negative:ButtonId,
+	positive:ButtonId,
+) {
+	let button = |value:i32| if value < 0 { negative } else { positive };
+
+	if old != 0 && old.signum() != new.signum() {
+		events.push(DeviceEvent::Button { button:button(old), state:ElementState::Released });
+	}
+	if new != 0 && old.signum() != new.signum() {
+		events.push(DeviceEvent::Button { button:button(new), state:ElementState::Pressed });
+	}
+}
+
+fn test_bit(bits:&[u8], bit:u16) -> bool {
+	bits.get(bit as usize / 8).map(|byte| byte & (1 << (bit % 8)) != 0).unwrap_or(false)
+}
+
+// The `EVIOC*` ioctl requests, see `linux/input.h`.
+const fn ioc_read(nr:u32, size:usize) -> c_ulong {
+	const IOC_READ:u32 = 2;
+	((IOC_READ << 30) | ((size as u32) << 16) | ((b'E' as u32) << 8) | nr) as c_ulong
+}
+
+const fn eviocgid() -> c_ulong { ioc_read(0x02, mem::size_of::<libc::input_id>()) }
+
+const fn eviocgname(len:usize) -> c_ulong { ioc_read(0x06, len) }
+
+const fn eviocgbit(ev:u16, len:usize) -> c_ulong { ioc_read(0x20 + ev as u32, len) }
+
+const fn eviocgabs(abs:u16) -> c_ulong {
+	ioc_read(0x40 + abs as u32, mem::size_of::<libc::input_absinfo>())
+}
+
+/// Watches `/dev/input` for device nodes being created and removed.
+struct Inotify {
+	fd:c_int,
+}
+
+impl Inotify {
+	fn new() -> Option<Self> {
+		let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+		if fd < 0 {
+			return None;
+		}
+		let inotify = Self { fd };
+
+		let dir = format!("{}\0", INPUT_DIR);
+		// udev fixes up the permissions of new nodes after creating them, hence `IN_ATTRIB`.
+		let mask = libc::IN_CREATE | libc::IN_ATTRIB | libc::IN_DELETE;
+		if unsafe { libc::inotify_add_watch(fd, dir.as_ptr() as _, mask) } < 0 {
+			return None;
+		}
+
+		Some(inotify)
+	}
+
+	/// Returns the paths that changed, and whether they were created (or had their
+	/// attributes changed) rather than deleted.
+	fn read_events(&self) -> Vec<(PathBuf, bool)> {
+		let mut changes = Vec::new();
+		let mut buffer = [0u8; 4096];
+
+		loop {
+			let read =
+				unsafe { libc::read(self.fd, buffer.as_mut_ptr() as *mut c_void, buffer.len()) };
+			if read <= 0 {
+				return changes;
+			}
+
+			let mut offset = 0;
+			while offset + mem::size_of::<libc::inotify_event>() <= read as usize {
+				let event:libc::inotify_event = unsafe {
+					ptr::read_unaligned(buffer.as_ptr().add(offset) as *const libc::inotify_event)
+				};
+				let name_start = offset + mem::size_of::<libc::inotify_event>();
+				let name = &buffer[name_start..name_start + event.len as usize];
+				let len = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+				let path = Path::new(INPUT_DIR).join(OsStr::from_bytes(&name[..len]));
+
+				changes.push((path, event.mask & libc::IN_DELETE == 0));
+				offset = name_start + event.len as usize;
+			}
+		}
+	}
+}
+
+impl Drop for Inotify {
+	fn drop(&mut self) {
+		unsafe { libc::close(self.fd) };
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn absinfo(minimum:i32, maximum:i32) -> libc::input_absinfo {
+		libc::input_absinfo { value:0, minimum, maximum, fuzz:0, flat:0, resolution:0 }
+	}
+
+	#[test]
+	fn maps_buttons() {
+		assert_eq!(map_button(0x130), gamepad::BUTTON_SOUTH);
+		assert_eq!(map_button(0x131), gamepad::BUTTON_EAST);
+		assert_eq!(map_button(0x133), gamepad::BUTTON_NORTH);
+		assert_eq!(map_button(0x134), gamepad::BUTTON_WEST);
+		assert_eq!(map_button(0x13b), gamepad::BUTTON_START);
+		assert_eq!(map_button(0x13c), gamepad::BUTTON_MODE);
+		assert_eq!(map_button(0x223), gamepad::BUTTON_DPAD_RIGHT);
+		assert_eq!(map_button(0x12f), gamepad::OTHER + 0x12f);
+	}
+
+	#[test]
+	fn maps_axes() {
+		assert_eq!(map_axis(0x00), (gamepad::AXIS_LEFT_STICK_X, false));
+		assert_eq!(map_axis(0x04), (gamepad::AXIS_RIGHT_STICK_Y, false));
+		assert_eq!(map_axis(0x02), (gamepad::AXIS_LEFT_TRIGGER, true));
+		assert_eq!(map_axis(0x0a), (gamepad::AXIS_LEFT_TRIGGER, true));
+		assert_eq!(map_axis(0x05), (gamepad::AXIS_RIGHT_TRIGGER, true));
+		assert_eq!(map_axis(0x09), (gamepad::AXIS_RIGHT_TRIGGER, true));
+		assert_eq!(map_axis(ABS_HAT0X), (gamepad::OTHER + ABS_HAT0X as AxisId, false));
+	}
+
+	#[test]
+	fn normalizes_axes() {
+		let stick = absinfo(-32768, 32767);
+		assert_eq!(normalize(&stick, -32768, false), Some(-1.0));
+		assert_eq!(normalize(&stick, 32767, false), Some(1.0));
+		assert_eq!(normalize(&stick, 40000, false), Some(1.0));
+
+		let trigger = absinfo(0, 255);
+		assert_eq!(normalize(&trigger, 0, true), Some(0.0));
+		assert_eq!(normalize(&trigger, 255, true), Some(1.0));
+
+		assert_eq!(normalize(&absinfo(0, 0), 0, false), None);
+	}
+
+	#[test]
+	fn translates_hats() {
+		let (left, right) = (gamepad::BUTTON_DPAD_LEFT, gamepad::BUTTON_DPAD_RIGHT);
+		let button = |button, state| DeviceEvent::Button { button, state };
+
+		let mut events = Vec::new();
+		hat_events(&mut events, 0, -1, left, right);
+		assert_eq!(events, [button(left, ElementState::Pressed)]);
+
+		let mut events = Vec::new();
+		hat_events(&mut events, -1, 1, left, right);
+		assert_eq!(events, [
+			button(left, ElementState::Released),
+			button(right, ElementState::Pressed)
+		]);
+
+		let mut events = Vec::new();
+		hat_events(&mut events, 1, 0, left, right);
+		assert_eq!(events, [button(right, ElementState::Released)]);
+
+		let mut events = Vec::new();
+		hat_events(&mut events, 1, 1, left, right);
+		assert!(events.is_empty());
+	}
+
+	#[test]
+	fn tests_bits() {
+		let bits = [0b0000_0001, 0b1000_0000];
+		assert!(test_bit(&bits, 0));
+		assert!(!test_bit(&bits, 1));
+		assert!(test_bit(&bits, 15));
+		assert!(!test_bit(&bits, 16));
+	}
+}
@@ -0,0 +1,96 @@
+use std::{
+	cell::{Cell, RefCell},
+	rc::Rc,
+};
+
+use gtk::prelude::*;
+
+use crate::{
+	dpi::PhysicalPosition,
+	window::{HitTest, HitTestResult},
+};
+
+/// The width of the resize border of undecorated windows, before scaling.
+const DEFAULT_RESIZE_BORDER:u32 = 5;
+
+/// Classifies the points of a window for its event handlers, shared with the
+/// `Window` that configures it.
+pub struct HitTester {
+	hit_test:RefCell<Option<Rc<HitTest>>>,
+	resize_border:Cell<u32>,
+	/// Where the caption was pressed, in window coordinates, until the press
+	/// turns into a move or the button is released.
+	caption_press:Cell<Option<(f64, f64)>>,
+}
+
+impl Default for HitTester {
+	fn default() -> Self {
+		Self {
+			hit_test:RefCell::new(None),
+			resize_border:Cell::new(DEFAULT_RESIZE_BORDER),
+			caption_press:Cell::new(None),
+		}
+	}
+}
+
+impl HitTester {
+	pub fn set_hit_test(&self, hit_test:Option<HitTest>) {
+		self.hit_test.replace(hit_test.map(Rc::new));
+	}
+
+	pub fn set_resize_border(&self, width:u32) { self.resize_border.set(width); }
+
+	/// Classifies the point at the root coordinates `(x, y)`. The resize border
+	/// is only tested if `resizable`.
+	pub fn test(&self, window:&gtk::Window, (x, y):(f64, f64), resizable:bool) -> HitTestResult {
+		let (left, top) = window.position();
+
+		let (width, height) = window.size();
+
+		let scale_factor = window.scale_factor();
+
+		if resizable {
+			let border = scale_factor * self.resize_border.get() as i32;
+
+			if let Some(direction) = crate::window::hit_test(
+				(left, top, left + width, top + height),
+				x as _,
+				y as _,
+				border,
+				border,
+			) {
+				return HitTestResult::Resize(direction);
+			}
+		}
+
+		let point = PhysicalPosition::new(
+			(x - left as f64) * scale_factor as f64,
+			(y - top as f64) * scale_factor as f64,
+		);
+
+		// The callback may replace the hit test.
+		let hit_test = self.hit_test.borrow().clone();
+
+		hit_test.map_or(HitTestResult::Client, |hit_test| hit_test.test(point))
+	}
+
+	pub fn press_caption(&self, position:(f64, f64)) { self.caption_press.set(Some(position)); }
+
+	/// Returns whether the pointer, at the window coordinates `(x, y)`, moved
+	/// far enough from where the caption was pressed to start moving the window.
+	pub fn take_caption_drag(&self, window:&gtk::Window, (x, y):(f64, f64)) -> bool {
+		let Some((start_x, start_y)) = self.caption_press.get() else {
+			return false;
+		};
+
+		if !window.drag_check_threshold(start_x as i32, start_y as i32, x as i32, y as i32) {
+			return false;
+		}
+
+		self.caption_press.set(None);
+
+		true
+	}
+
+	pub fn release_caption(&self) { self.caption_press.set(None); }
+}
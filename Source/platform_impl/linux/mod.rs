@@ -2,12 +2,22 @@
 // Copyright 2021-2023 Tauri Programme within The Commons Conservancy
 // SPDX-License-Identifier: Apache-2.0
 
+mod clipboard;
 mod device;
+mod drag;
 mod event_loop;
+#[cfg(target_os = "linux")]
+mod gamepad;
+mod hit_test;
 mod icon;
 mod keyboard;
 mod keycode;
 mod monitor;
+mod notification;
+mod power;
+mod session;
+#[cfg(test)]
+mod test_bus;
 mod util;
 mod window;
 
@@ -16,13 +26,15 @@ pub mod wayland;
 pub mod x11;
 
 pub(crate) use event_loop::PlatformSpecificEventLoopAttributes;
+pub use clipboard::Clipboard;
 pub use event_loop::{EventLoop, EventLoopProxy, EventLoopWindowTarget};
 pub use icon::PlatformIcon;
 pub use monitor::{MonitorHandle, VideoMode};
+pub use session::InhibitCookie;
 pub use window::{Window, WindowId};
 
 pub use self::keycode::{keycode_from_scancode, keycode_to_scancode};
-use crate::{event::DeviceId as RootDeviceId, keyboard::Key};
+use crate::{event::DeviceId as RootDeviceId, keyboard::Key, platform::unix::WindowType};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct KeyEventExtra {
@@ -44,26 +56,42 @@ impl Default for Parent {
 #[derive(Clone)]
 pub struct PlatformSpecificWindowBuilderAttributes {
 	pub parent:Parent,
+	pub destroy_with_parent:bool,
 	pub skip_taskbar:bool,
+	pub skip_pager:bool,
+	pub demands_attention:bool,
+	pub window_type:WindowType,
+	pub name:Option<(String, String)>,
+	pub role:Option<String>,
 	pub auto_transparent:bool,
 	pub double_buffered:bool,
 	pub app_paintable:bool,
 	pub rgba_visual:bool,
 	pub cursor_moved:bool,
 	pub default_vbox:bool,
+	#[cfg(feature = "layer-shell")]
+	pub layer_shell:wayland::layer_shell::LayerShellAttributes,
 }
 
 impl Default for PlatformSpecificWindowBuilderAttributes {
 	fn default() -> Self {
 		Self {
 			parent:Default::default(),
+			destroy_with_parent:false,
 			skip_taskbar:Default::default(),
+			skip_pager:Default::default(),
+			demands_attention:Default::default(),
+			window_type:Default::default(),
+			name:None,
+			role:None,
 			auto_transparent:true,
 			double_buffered:true,
 			app_paintable:false,
 			rgba_visual:false,
 			cursor_moved:true,
 			default_vbox:true,
+			#[cfg(feature = "layer-shell")]
+			layer_shell:Default::default(),
 		}
 	}
 }
@@ -83,8 +111,29 @@ pub struct DeviceId(usize);
 
 impl DeviceId {
 	pub unsafe fn dummy() -> Self { Self(0) }
+
+	pub fn name(&self) -> Option<String> {
+		#[cfg(target_os = "linux")]
+		return gamepad::device_info(*self).map(|info| info.name);
+		#[cfg(not(target_os = "linux"))]
+		return None;
+	}
+
+	pub fn vendor_id(&self) -> Option<u16> {
+		#[cfg(target_os = "linux")]
+		return gamepad::device_info(*self).map(|info| info.vendor_id);
+		#[cfg(not(target_os = "linux"))]
+		return None;
+	}
+
+	pub fn product_id(&self) -> Option<u16> {
+		#[cfg(target_os = "linux")]
+		return gamepad::device_info(*self).map(|info| info.product_id);
+		#[cfg(not(target_os = "linux"))]
+		return None;
+	}
 }
 
-// FIXME: currently we use a dummy device id, find if we can get device id from
-// gtk
+// FIXME: currently we use a dummy device id for everything but game
+// controllers, find if we can get device id from gtk
 pub(crate) const DEVICE_ID:RootDeviceId = RootDeviceId(DeviceId(0));
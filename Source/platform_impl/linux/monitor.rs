@@ -2,11 +2,19 @@
 // Copyright 2021-2023 Tauri Programme within The Commons Conservancy
 // SPDX-License-Identifier: Apache-2.0
 
-use gtk::gdk::{self, Display, prelude::MonitorExt};
+use gtk::{
+	gdk::{
+		self,
+		Display,
+		prelude::{DisplayExtManual, MonitorExt},
+	},
+	prelude::{GtkApplicationExt, GtkWindowExt, WidgetExt},
+};
 
 use crate::{
 	dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
-	monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
+	event::Event,
+	monitor::{MonitorHandle as RootMonitorHandle, SubpixelLayout, VideoMode as RootVideoMode},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -42,10 +50,63 @@ impl MonitorHandle {
 	#[inline]
 	pub fn scale_factor(&self) -> f64 { self.monitor.scale_factor() as f64 }
 
+	pub fn work_area(&self) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+		let rect = self.monitor.workarea();
+
+		let scale_factor = self.scale_factor();
+
+		(
+			LogicalPosition { x:rect.x(), y:rect.y() }.to_physical(scale_factor),
+			LogicalSize { width:rect.width() as u32, height:rect.height() as u32 }
+				.to_physical(scale_factor),
+		)
+	}
+
 	#[inline]
 	pub fn video_modes(&self) -> Box<dyn Iterator<Item = RootVideoMode>> {
 		Box::new(Vec::new().into_iter())
 	}
+
+	#[inline]
+	pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+		let (width, height) = (self.monitor.width_mm(), self.monitor.height_mm());
+
+		if width > 0 && height > 0 { Some((width as u32, height as u32)) } else { None }
+	}
+
+	#[inline]
+	pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+		// GDK reports 0 when the refresh rate is unknown.
+		let refresh_rate = self.monitor.refresh_rate();
+
+		if refresh_rate > 0 { Some(refresh_rate as u32) } else { None }
+	}
+
+	#[inline]
+	pub fn manufacturer(&self) -> Option<String> {
+		self.monitor.manufacturer().map(|s| s.as_str().to_string())
+	}
+
+	#[inline]
+	pub fn connector_name(&self) -> Option<String> {
+		// On X11, GDK fills the monitor model with the RandR output name, which is
+		// the connector. Wayland only exposes the make and model of the output.
+		let is_x11 = self.monitor.display().map(|d| d.backend().is_x11()).unwrap_or(false);
+
+		if is_x11 { self.monitor.model().map(|s| s.as_str().to_string()) } else { None }
+	}
+
+	#[inline]
+	pub fn subpixel_layout(&self) -> SubpixelLayout {
+		match self.monitor.subpixel_layout() {
+			gdk::SubpixelLayout::None => SubpixelLayout::None,
+			gdk::SubpixelLayout::HorizontalRgb => SubpixelLayout::HorizontalRgb,
+			gdk::SubpixelLayout::HorizontalBgr => SubpixelLayout::HorizontalBgr,
+			gdk::SubpixelLayout::VerticalRgb => SubpixelLayout::VerticalRgb,
+			gdk::SubpixelLayout::VerticalBgr => SubpixelLayout::VerticalBgr,
+			_ => SubpixelLayout::Unknown,
+		}
+	}
 }
 
 unsafe impl Send for MonitorHandle {}
@@ -78,3 +139,80 @@ pub fn from_point(display:&Display, x:f64, y:f64) -> Option<MonitorHandle> {
 		None
 	}
 }
+
+impl From<&gdk::Monitor> for RootMonitorHandle {
+	fn from(monitor:&gdk::Monitor) -> Self {
+		RootMonitorHandle { inner:MonitorHandle { monitor:monitor.clone() } }
+	}
+}
+
+/// Sends `Event::MonitorChanged` whenever the geometry or the scale factor of
+/// `monitor` changes.
+pub fn watch<T:'static>(
+	monitor:&gdk::Monitor,
+	event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+) {
+	let tx = event_tx.clone();
+	monitor.connect_geometry_notify(move |monitor| {
+		send_event(&tx, Event::MonitorChanged(monitor.into()));
+	});
+
+	let tx = event_tx.clone();
+	monitor.connect_scale_factor_notify(move |monitor| {
+		send_event(&tx, Event::MonitorChanged(monitor.into()));
+	});
+}
+
+pub fn send_event<T:'static>(
+	event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+	event:Event<'static, T>,
+) {
+	if let Err(e) = event_tx.send(event) {
+		log::warn!("Failed to send monitor event to event channel: {}", e);
+	}
+}
+
+/// Moves the windows of `app` which are no longer visible on any monitor after
+/// `removed` was disconnected onto the primary monitor.
+///
+/// The offset of a window from the top-left corner of the removed monitor is
+/// kept when it fits in the work area of the primary monitor, otherwise the
+/// window is clamped to it.
+pub fn relocate_windows(app:&gtk::Application, display:&Display, removed:&gdk::Monitor) {
+	let monitors:Vec<gdk::Monitor> = (0..display.n_monitors())
+		.filter_map(|i| display.monitor(i))
+		.filter(|m| m != removed)
+		.collect();
+
+	let Some(target) =
+		display.primary_monitor().filter(|m| m != removed).or_else(|| monitors.first().cloned())
+	else {
+		return;
+	};
+
+	let removed_rect = removed.geometry();
+
+	let workarea = target.workarea();
+
+	for window in app.windows() {
+		let Some(gdk_window) = window.window() else {
+			continue;
+		};
+
+		let (x, y) = gdk_window.root_origin();
+
+		let (width, height) = window.size();
+
+		let rect = gdk::Rectangle::new(x, y, width, height);
+
+		if monitors.iter().any(|m| m.geometry().intersect(&rect).is_some()) {
+			continue;
+		}
+
+		let offset_x = (x - removed_rect.x()).clamp(0, (workarea.width() - width).max(0));
+
+		let offset_y = (y - removed_rect.y()).clamp(0, (workarea.height() - height).max(0));
+
+		window.move_(workarea.x() + offset_x, workarea.y() + offset_y);
+	}
+}
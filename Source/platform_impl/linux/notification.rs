@@ -0,0 +1,659 @@
+//! Desktop notifications over D-Bus.
+//!
+//! Notifications are sent to the `org.freedesktop.Notifications` server, or to
+//! the notification portal when running in a Flatpak sandbox. Both are
+//! addressed by their well-known names on the session bus, so a mock server on
+//! the bus given by `DBUS_SESSION_BUS_ADDRESS` can stand in for them.
+
+use std::{
+	cell::{Cell, RefCell},
+	collections::{HashMap, HashSet},
+	path::Path,
+	rc::Rc,
+};
+
+use gtk::{
+	gdk_pixbuf::Pixbuf,
+	gio::{self, BusType, Cancellable, DBusCallFlags, DBusConnection, DBusSignalFlags},
+	glib::{self, ToVariant, VariantTy},
+	prelude::*,
+};
+
+use crate::{
+	error::ExternalError,
+	event::{Event, NotificationEvent},
+	icon::Icon,
+	notification::{Notification, NotificationId, Urgency},
+};
+
+const SERVER_NAME:&str = "org.freedesktop.Notifications";
+
+const SERVER_PATH:&str = "/org/freedesktop/Notifications";
+
+const PORTAL_NAME:&str = "org.freedesktop.portal.Desktop";
+
+const PORTAL_PATH:&str = "/org/freedesktop/portal/desktop";
+
+const PORTAL_INTERFACE:&str = "org.freedesktop.portal.Notification";
+
+/// The action invoked by clicking the notification itself.
+const DEFAULT_ACTION:&str = "default";
+
+const REPLY_ACTION:&str = "inline-reply";
+
+/// The `NotificationClosed` reason of a notification dismissed by the user.
+const DISMISSED_BY_USER:u32 = 2;
+
+pub struct Notifier {
+	connection:Option<DBusConnection>,
+	portal:bool,
+	/// The notifications that weren't closed yet, the signals of the server are
+	/// broadcast to every application.
+	ids:Rc<RefCell<HashSet<u32>>>,
+	/// The portal lets the application choose the ids.
+	next_portal_id:Cell<u32>,
+}
+
+impl Notifier {
+	/// Connects to the notification server and sends the user's responses as
+	/// `Event::Notification`.
+	pub fn new<T:'static>(event_tx:&crossbeam_channel::Sender<Event<'static, T>>) -> Self {
+		let portal = Path::new("/.flatpak-info").exists();
+
+		let connection = match gio::bus_get_sync(BusType::Session, None::<&Cancellable>) {
+			Ok(connection) => Some(connection),
+			Err(e) => {
+				log::warn!("Failed to connect to the session bus, notifications are disabled: {}", e);
+
+				None
+			},
+		};
+
+		Self::with_connection(connection, portal, event_tx)
+	}
+
+	fn with_connection<T:'static>(
+		connection:Option<DBusConnection>,
+		portal:bool,
+		event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+	) -> Self {
+		let ids:Rc<RefCell<HashSet<u32>>> = Rc::default();
+
+		if let Some(connection) = &connection {
+			if portal {
+				watch_portal(connection, &ids, event_tx);
+			} else {
+				watch_server(connection, &ids, event_tx);
+			}
+		}
+
+		Self { connection, portal, ids, next_portal_id:Cell::new(1) }
+	}
+
+	pub fn show(
+		&self,
+		notification:&Notification,
+		replaces:Option<NotificationId>,
+	) -> Result<NotificationId, ExternalError> {
+		let connection = self.connection()?;
+
+		let id = if self.portal {
+			let id = replaces.map(|id| id.0).unwrap_or_else(|| {
+				let id = self.next_portal_id.get();
+				self.next_portal_id.set(id + 1);
+				id
+			});
+
+			add_portal_notification(connection, id, notification)?;
+
+			id
+		} else {
+			notify(connection, notification, replaces.map_or(0, |id| id.0))?
+		};
+
+		self.ids.borrow_mut().insert(id);
+
+		Ok(NotificationId(id))
+	}
+
+	pub fn close(&self, id:NotificationId) -> Result<(), ExternalError> {
+		let connection = self.connection()?;
+
+		if self.portal {
+			// The portal doesn't report closed notifications.
+			self.ids.borrow_mut().remove(&id.0);
+
+			call(
+				connection,
+				PORTAL_NAME,
+				PORTAL_PATH,
+				PORTAL_INTERFACE,
+				"RemoveNotification",
+				&(id.0.to_string(),).to_variant(),
+				None,
+			)
+		} else {
+			call(
+				connection,
+				SERVER_NAME,
+				SERVER_PATH,
+				SERVER_NAME,
+				"CloseNotification",
+				&(id.0,).to_variant(),
+				None,
+			)
+		}
+		.map(drop)
+	}
+
+	fn connection(&self) -> Result<&DBusConnection, ExternalError> {
+		self.connection.as_ref().ok_or_else(|| ExternalError::Os(os_error!(super::OsError)))
+	}
+}
+
+fn notify(
+	connection:&DBusConnection,
+	notification:&Notification,
+	replaces_id:u32,
+) -> Result<u32, ExternalError> {
+	let mut actions = vec![DEFAULT_ACTION.to_string(), String::new()];
+
+	for (id, label) in &notification.actions {
+		actions.extend([id.clone(), label.clone()]);
+	}
+
+	let mut hints:HashMap<String, glib::Variant> = HashMap::new();
+
+	let urgency:u8 = match notification.urgency {
+		Urgency::Low => 0,
+		Urgency::Normal => 1,
+		Urgency::Critical => 2,
+	};
+
+	hints.insert("urgency".into(), urgency.to_variant());
+
+	if let Some(icon) = &notification.icon {
+		hints.insert("image-data".into(), image_data(icon));
+	}
+
+	if let Some(placeholder) = &notification.reply_placeholder {
+		if capabilities(connection).iter().any(|capability| capability == REPLY_ACTION) {
+			actions.extend([REPLY_ACTION.to_string(), String::new()]);
+
+			hints.insert("x-kde-reply-placeholder-text".into(), placeholder.to_variant());
+		}
+	}
+
+	let parameters = (
+		app_name(),
+		replaces_id,
+		"",
+		notification.title.as_str(),
+		notification.body.as_str(),
+		actions,
+		hints,
+		-1i32,
+	)
+		.to_variant();
+
+	call(connection, SERVER_NAME, SERVER_PATH, SERVER_NAME, "Notify", &parameters, Some("(u)"))?
+		.get::<(u32,)>()
+		.map(|(id,)| id)
+		.ok_or_else(|| ExternalError::Os(os_error!(super::OsError)))
+}
+
+fn add_portal_notification(
+	connection:&DBusConnection,
+	id:u32,
+	notification:&Notification,
+) -> Result<(), ExternalError> {
+	let mut properties:HashMap<String, glib::Variant> = HashMap::from([
+		("title".into(), notification.title.to_variant()),
+		("body".into(), notification.body.to_variant()),
+		("default-action".into(), DEFAULT_ACTION.to_variant()),
+	]);
+
+	let priority = match notification.urgency {
+		Urgency::Low => "low",
+		Urgency::Normal => "normal",
+		Urgency::Critical => "urgent",
+	};
+
+	properties.insert("priority".into(), priority.to_variant());
+
+	if let Some(icon) = notification.icon.clone() {
+		let pixbuf:Pixbuf = icon.inner.into();
+
+		if let Some(icon) = pixbuf.serialize() {
+			properties.insert("icon".into(), icon);
+		}
+	}
+
+	if !notification.actions.is_empty() {
+		let buttons:Vec<HashMap<String, glib::Variant>> = notification
+			.actions
+			.iter()
+			.map(|(id, label)| {
+				HashMap::from([
+					("label".to_string(), label.to_variant()),
+					("action".to_string(), id.to_variant()),
+				])
+			})
+			.collect();
+
+		properties.insert("buttons".into(), buttons.to_variant());
+	}
+
+	call(
+		connection,
+		PORTAL_NAME,
+		PORTAL_PATH,
+		PORTAL_INTERFACE,
+		"AddNotification",
+		&(id.to_string(), properties).to_variant(),
+		None,
+	)
+	.map(drop)
+}
+
+/// Returns the `image-data` hint, a `(iiibiiay)` of the pixels.
+fn image_data(icon:&Icon) -> glib::Variant {
+	let pixbuf:Pixbuf = icon.inner.clone().into();
+
+	(
+		pixbuf.width(),
+		pixbuf.height(),
+		pixbuf.rowstride(),
+		pixbuf.has_alpha(),
+		pixbuf.bits_per_sample(),
+		pixbuf.n_channels(),
+		pixbuf.read_pixel_bytes().to_vec(),
+	)
+		.to_variant()
+}
+
+fn capabilities(connection:&DBusConnection) -> Vec<String> {
+	call(connection, SERVER_NAME, SERVER_PATH, SERVER_NAME, "GetCapabilities", &().to_variant(), Some("(as)"))
+		.ok()
+		.and_then(|reply| reply.get::<(Vec<String>,)>())
+		.map(|(capabilities,)| capabilities)
+		.unwrap_or_default()
+}
+
+fn app_name() -> String {
+	glib::application_name().or_else(glib::prgname).map(Into::into).unwrap_or_else(|| "tao".into())
+}
+
+fn call(
+	connection:&DBusConnection,
+	name:&str,
+	path:&str,
+	interface:&str,
+	method:&str,
+	parameters:&glib::Variant,
+	reply_type:Option<&str>,
+) -> Result<glib::Variant, ExternalError> {
+	connection
+		.call_sync(
+			Some(name),
+			path,
+			interface,
+			method,
+			Some(parameters),
+			reply_type.and_then(|reply_type| VariantTy::new(reply_type).ok()),
+			DBusCallFlags::NONE,
+			-1,
+			None::<&Cancellable>,
+		)
+		.map_err(|_| ExternalError::Os(os_error!(super::OsError)))
+}
+
+fn watch_server<T:'static>(
+	connection:&DBusConnection,
+	ids:&Rc<RefCell<HashSet<u32>>>,
+	event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+) {
+	let (ids, event_tx) = (ids.clone(), event_tx.clone());
+
+	connection.signal_subscribe(
+		Some(SERVER_NAME),
+		Some(SERVER_NAME),
+		None,
+		Some(SERVER_PATH),
+		None,
+		DBusSignalFlags::NONE,
+		move |_, _, _, _, signal, parameters| {
+			let (id, event) = match signal {
+				"ActionInvoked" => {
+					let Some((id, action)) = parameters.get::<(u32, String)>() else {
+						return;
+					};
+
+					match action.as_str() {
+						DEFAULT_ACTION => (id, NotificationEvent::Activated),
+						// Followed by `NotificationReplied`.
+						REPLY_ACTION => return,
+						_ => (id, NotificationEvent::ActionInvoked(action)),
+					}
+				},
+				"NotificationReplied" => {
+					let Some((id, text)) = parameters.get::<(u32, String)>() else {
+						return;
+					};
+
+					(id, NotificationEvent::Replied(text))
+				},
+				"NotificationClosed" => {
+					let Some((id, reason)) = parameters.get::<(u32, u32)>() else {
+						return;
+					};
+
+					// Notifications that expired or were closed by the application aren't reported.
+					if ids.borrow_mut().remove(&id) && reason == DISMISSED_BY_USER {
+						send_event(&event_tx, id, NotificationEvent::Dismissed);
+					}
+
+					return;
+				},
+				_ => return,
+			};
+
+			if ids.borrow().contains(&id) {
+				send_event(&event_tx, id, event);
+			}
+		},
+	);
+}
+
+fn watch_portal<T:'static>(
+	connection:&DBusConnection,
+	ids:&Rc<RefCell<HashSet<u32>>>,
+	event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+) {
+	let (ids, event_tx) = (ids.clone(), event_tx.clone());
+
+	connection.signal_subscribe(
+		Some(PORTAL_NAME),
+		Some(PORTAL_INTERFACE),
+		Some("ActionInvoked"),
+		Some(PORTAL_PATH),
+		None,
+		DBusSignalFlags::NONE,
+		move |_, _, _, _, _, parameters| {
+			let Some((id, action, _)) = parameters.get::<(String, String, Vec<glib::Variant>)>()
+			else {
+				return;
+			};
+
+			// The notification is removed once it is activated.
+			let Some(id) = id.parse::<u32>().ok().filter(|id| ids.borrow_mut().remove(id)) else {
+				return;
+			};
+
+			let event = if action == DEFAULT_ACTION {
+				NotificationEvent::Activated
+			} else {
+				NotificationEvent::ActionInvoked(action)
+			};
+
+			send_event(&event_tx, id, event);
+		},
+	);
+}
+
+fn send_event<T>(
+	event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+	id:u32,
+	event:NotificationEvent,
+) {
+	if let Err(e) = event_tx.send(Event::Notification { id:NotificationId(id), event }) {
+		log::warn!("Failed to send notification event to event channel: {}", e);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::{
+		Arc,
+		Mutex,
+		atomic::{AtomicU32, Ordering},
+	};
+
+	use gtk::gio::DBusNodeInfo;
+
+	use super::{
+		super::test_bus::{self, Service, TestBus},
+		*,
+	};
+
+	const SERVER_INTROSPECTION:&str = r#"<node>
+		<interface name="org.freedesktop.Notifications">
+			<method name="GetCapabilities">
+				<arg type="as" direction="out"/>
+			</method>
+			<method name="Notify">
+				<arg type="s" direction="in"/>
+				<arg type="u" direction="in"/>
+				<arg type="s" direction="in"/>
+				<arg type="s" direction="in"/>
+				<arg type="s" direction="in"/>
+				<arg type="as" direction="in"/>
+				<arg type="a{sv}" direction="in"/>
+				<arg type="i" direction="in"/>
+				<arg type="u" direction="out"/>
+			</method>
+			<method name="CloseNotification">
+				<arg type="u" direction="in"/>
+			</method>
+		</interface>
+	</node>"#;
+
+	const PORTAL_INTROSPECTION:&str = r#"<node>
+		<interface name="org.freedesktop.portal.Notification">
+			<method name="AddNotification">
+				<arg type="s" direction="in"/>
+				<arg type="a{sv}" direction="in"/>
+			</method>
+			<method name="RemoveNotification">
+				<arg type="s" direction="in"/>
+			</method>
+		</interface>
+	</node>"#;
+
+	type NotifyParameters =
+		(String, u32, String, String, String, Vec<String>, HashMap<String, glib::Variant>, i32);
+
+	/// Serves the methods of `interface` with `reply`, which returns the
+	/// output arguments.
+	fn serve(
+		bus:&TestBus,
+		name:&str,
+		path:&'static str,
+		introspection:&'static str,
+		interface:&'static str,
+		reply:impl Fn(&str, glib::Variant) -> Option<glib::Variant> + Send + Sync + 'static,
+	) -> Service {
+		bus.serve(&[name], move |connection| {
+			let interface = DBusNodeInfo::for_xml(introspection)
+				.ok()
+				.and_then(|node| node.lookup_interface(interface))
+				.unwrap();
+
+			connection
+				.register_object(
+					path,
+					&interface,
+					move |_, _, _, _, method, parameters, invocation| {
+						invocation.return_value(reply(method, parameters).as_ref())
+					},
+					|_, _, _, _, _| ().to_variant(),
+					|_, _, _, _, _, _| false,
+				)
+				.unwrap();
+		})
+	}
+
+	fn emit(service:&Service, path:&str, interface:&str, signal:&str, parameters:glib::Variant) {
+		service.connection.emit_signal(None, path, interface, signal, Some(&parameters)).unwrap();
+	}
+
+	/// Waits for `count` notification events.
+	fn receive(
+		context:&glib::MainContext,
+		event_rx:&crossbeam_channel::Receiver<Event<'static, ()>>,
+		count:usize,
+	) -> Vec<(NotificationId, NotificationEvent)> {
+		let mut events = Vec::new();
+
+		test_bus::wait_for(context, || {
+			events.extend(event_rx.try_iter().filter_map(|event| {
+				match event {
+					Event::Notification { id, event } => Some((id, event)),
+					_ => None,
+				}
+			}));
+
+			(events.len() >= count).then_some(())
+		});
+
+		events
+	}
+
+	#[test]
+	fn server_events() {
+		let bus = TestBus::new();
+
+		let notified:Arc<Mutex<Vec<NotifyParameters>>> = Arc::default();
+
+		let notified_ = notified.clone();
+		let next_id = AtomicU32::new(7);
+		let service = serve(
+			&bus,
+			SERVER_NAME,
+			SERVER_PATH,
+			SERVER_INTROSPECTION,
+			SERVER_NAME,
+			move |method, parameters| {
+				match method {
+					"GetCapabilities" => Some((vec!["actions", REPLY_ACTION],).to_variant()),
+					"Notify" => {
+						notified_.lock().unwrap().extend(parameters.get::<NotifyParameters>());
+
+						Some((next_id.fetch_add(1, Ordering::Relaxed),).to_variant())
+					},
+					_ => None,
+				}
+			},
+		);
+
+		let context = glib::MainContext::new();
+
+		context
+			.with_thread_default(|| {
+				let client = bus.connect(&[]);
+
+				let (event_tx, event_rx) = crossbeam_channel::unbounded();
+
+				let notifier = Notifier::with_connection(Some(client.clone()), false, &event_tx);
+
+				test_bus::round_trip(&client);
+
+				let notification = Notification::new("Title")
+					.with_body("Body")
+					.with_action("open", "Open")
+					.with_reply("Reply");
+
+				assert_eq!(notifier.show(&notification, None).unwrap(), NotificationId(7));
+
+				{
+					let notified = notified.lock().unwrap();
+
+					let (_, replaces_id, _, title, body, actions, hints, _) = &notified[0];
+
+					assert_eq!(*replaces_id, 0);
+					assert_eq!((title.as_str(), body.as_str()), ("Title", "Body"));
+					assert_eq!(actions, &[DEFAULT_ACTION, "", "open", "Open", REPLY_ACTION, ""]);
+					assert_eq!(hints["urgency"].get::<u8>(), Some(1));
+					assert_eq!(
+						hints["x-kde-reply-placeholder-text"].get::<String>().as_deref(),
+						Some("Reply")
+					);
+				}
+
+				let signal = |signal:&str, parameters:glib::Variant| {
+					emit(&service, SERVER_PATH, SERVER_NAME, signal, parameters)
+				};
+
+				signal("ActionInvoked", (7u32, DEFAULT_ACTION).to_variant());
+				signal("ActionInvoked", (7u32, "open").to_variant());
+				signal("ActionInvoked", (7u32, REPLY_ACTION).to_variant());
+				signal("NotificationReplied", (7u32, "Hi").to_variant());
+				// Notifications of other applications are ignored.
+				signal("ActionInvoked", (8u32, DEFAULT_ACTION).to_variant());
+				signal("NotificationClosed", (7u32, DISMISSED_BY_USER).to_variant());
+
+				assert_eq!(receive(&context, &event_rx, 4), [
+					(NotificationId(7), NotificationEvent::Activated),
+					(NotificationId(7), NotificationEvent::ActionInvoked("open".into())),
+					(NotificationId(7), NotificationEvent::Replied("Hi".into())),
+					(NotificationId(7), NotificationEvent::Dismissed),
+				]);
+			})
+			.unwrap();
+	}
+
+	#[test]
+	fn portal_events() {
+		let bus = TestBus::new();
+
+		let service = serve(
+			&bus,
+			PORTAL_NAME,
+			PORTAL_PATH,
+			PORTAL_INTROSPECTION,
+			PORTAL_INTERFACE,
+			|_, _| None,
+		);
+
+		let context = glib::MainContext::new();
+
+		context
+			.with_thread_default(|| {
+				let client = bus.connect(&[]);
+
+				let (event_tx, event_rx) = crossbeam_channel::unbounded();
+
+				let notifier = Notifier::with_connection(Some(client.clone()), true, &event_tx);
+
+				test_bus::round_trip(&client);
+
+				let notification = Notification::new("Title").with_action("open", "Open");
+
+				assert_eq!(notifier.show(&notification, None).unwrap(), NotificationId(1));
+				assert_eq!(notifier.show(&notification, None).unwrap(), NotificationId(2));
+
+				let invoke = |id:&str, action:&str| {
+					emit(
+						&service,
+						PORTAL_PATH,
+						PORTAL_INTERFACE,
+						"ActionInvoked",
+						(id, action, Vec::<glib::Variant>::new()).to_variant(),
+					)
+				};
+
+				invoke("1", DEFAULT_ACTION);
+				// The first notification was removed when it was activated.
+				invoke("1", "open");
+				invoke("2", "open");
+
+				assert_eq!(receive(&context, &event_rx, 2), [
+					(NotificationId(1), NotificationEvent::Activated),
+					(NotificationId(2), NotificationEvent::ActionInvoked("open".into())),
+				]);
+
+				assert!(notifier.ids.borrow().is_empty());
+			})
+			.unwrap();
+	}
+}
@@ -0,0 +1,275 @@
+//! Power events from the system bus.
+//!
+//! Sleep is reported by logind, the power source by UPower and the low-power
+//! mode by power-profiles-daemon. The services are addressed by their well-known
+//! names, so a mock service on the bus given by `DBUS_SYSTEM_BUS_ADDRESS` can
+//! stand in for them.
+
+use std::{cell::Cell, collections::HashMap, rc::Rc};
+
+use gtk::{
+	gio::{self, BusType, Cancellable, DBusCallFlags, DBusConnection, DBusSignalFlags},
+	glib::{self, ToVariant},
+};
+
+use crate::event::{Event, PowerEvent, PowerSource};
+
+const PROPERTIES_INTERFACE:&str = "org.freedesktop.DBus.Properties";
+
+/// Subscribes to the power signals of the system bus, sending them as
+/// `Event::Power`.
+pub fn watch<T:'static>(event_tx:&crossbeam_channel::Sender<Event<'static, T>>) {
+	let event_tx = event_tx.clone();
+	gio::bus_get(BusType::System, None::<&Cancellable>, move |connection| {
+		match connection {
+			Ok(connection) => watch_connection(&connection, &event_tx),
+			Err(e) => {
+				log::warn!("Failed to connect to the system bus, power events are disabled: {}", e)
+			},
+		}
+	});
+}
+
+fn watch_connection<T:'static>(
+	connection:&DBusConnection,
+	event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+) {
+	let event_tx_ = event_tx.clone();
+	connection.signal_subscribe(
+		Some("org.freedesktop.login1"),
+		Some("org.freedesktop.login1.Manager"),
+		Some("PrepareForSleep"),
+		Some("/org/freedesktop/login1"),
+		None,
+		DBusSignalFlags::NONE,
+		move |_, _, _, _, _, parameters| {
+			if let Some((sleeping,)) = parameters.get::<(bool,)>() {
+				send_event(&event_tx_, if sleeping {
+					PowerEvent::Suspending
+				} else {
+					PowerEvent::Resumed
+				});
+			}
+		},
+	);
+
+	let event_tx_ = event_tx.clone();
+	watch_property(
+		connection,
+		"org.freedesktop.UPower",
+		"/org/freedesktop/UPower",
+		"org.freedesktop.UPower",
+		"OnBattery",
+		move |value| {
+			if let Some(on_battery) = value.get::<bool>() {
+				let source = if on_battery { PowerSource::Battery } else { PowerSource::Ac };
+
+				send_event(&event_tx_, PowerEvent::PowerSourceChanged(source));
+			}
+		},
+	);
+
+	// The profile may change between balanced and performance, which isn't a
+	// change of the low-power mode.
+	let low_power = Rc::new(Cell::new(false));
+	let low_power_ = low_power.clone();
+	property(
+		connection,
+		"net.hadess.PowerProfiles",
+		"/net/hadess/PowerProfiles",
+		"ActiveProfile",
+		move |value| {
+			low_power_.set(value.get::<String>().is_some_and(|profile| profile == "power-saver"));
+		},
+	);
+	let event_tx_ = event_tx.clone();
+	watch_property(
+		connection,
+		"net.hadess.PowerProfiles",
+		"/net/hadess/PowerProfiles",
+		"net.hadess.PowerProfiles",
+		"ActiveProfile",
+		move |value| {
+			if let Some(profile) = value.get::<String>() {
+				let enabled = profile == "power-saver";
+				if low_power.replace(enabled) != enabled {
+					send_event(&event_tx_, PowerEvent::LowPowerModeChanged(enabled));
+				}
+			}
+		},
+	);
+}
+
+/// Calls `f` with the current value of `property`, for interfaces named after
+/// their service.
+fn property<F:FnOnce(glib::Variant) + 'static>(
+	connection:&DBusConnection,
+	name:&str,
+	path:&str,
+	property:&str,
+	f:F,
+) {
+	connection.call(
+		Some(name),
+		path,
+		PROPERTIES_INTERFACE,
+		"Get",
+		Some(&(name, property).to_variant()),
+		glib::VariantTy::new("(v)").ok(),
+		DBusCallFlags::NONE,
+		-1,
+		None::<&Cancellable>,
+		move |reply| {
+			if let Some((value,)) = reply.ok().and_then(|reply| reply.get::<(glib::Variant,)>()) {
+				f(value);
+			}
+		},
+	);
+}
+
+/// Calls `f` with the new value of `property` whenever it changes.
+fn watch_property<F:Fn(&glib::Variant) + 'static>(
+	connection:&DBusConnection,
+	name:&str,
+	path:&str,
+	interface:&str,
+	property:&'static str,
+	f:F,
+) {
+	connection.signal_subscribe(
+		Some(name),
+		Some(PROPERTIES_INTERFACE),
+		Some("PropertiesChanged"),
+		Some(path),
+		Some(interface),
+		DBusSignalFlags::NONE,
+		move |_, _, _, _, _, parameters| {
+			if let Some((_, changed, _)) =
+				parameters.get::<(String, HashMap<String, glib::Variant>, Vec<String>)>()
+			{
+				if let Some(value) = changed.get(property) {
+					f(value);
+				}
+			}
+		},
+	);
+}
+
+fn send_event<T>(event_tx:&crossbeam_channel::Sender<Event<'static, T>>, event:PowerEvent) {
+	if let Err(e) = event_tx.send(Event::Power(event)) {
+		log::warn!("Failed to send power event to event channel: {}", e);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{super::test_bus::{self, TestBus}, *};
+
+	fn properties_changed(
+		service:&DBusConnection,
+		path:&str,
+		interface:&str,
+		property:&str,
+		value:glib::Variant,
+	) {
+		let changed = HashMap::from([(property.to_string(), value)]);
+
+		service
+			.emit_signal(
+				None,
+				path,
+				PROPERTIES_INTERFACE,
+				"PropertiesChanged",
+				Some(&(interface, changed, Vec::<String>::new()).to_variant()),
+			)
+			.unwrap();
+	}
+
+	#[test]
+	fn sends_power_events() {
+		let bus = TestBus::new();
+
+		let context = glib::MainContext::new();
+
+		context
+			.with_thread_default(|| {
+				// A single service keeps the order of the signals.
+				let service = bus.connect(&[
+					"org.freedesktop.login1",
+					"org.freedesktop.UPower",
+					"net.hadess.PowerProfiles",
+				]);
+
+				let client = bus.connect(&[]);
+
+				let (event_tx, event_rx) = crossbeam_channel::unbounded::<Event<'static, ()>>();
+
+				watch_connection(&client, &event_tx);
+
+				test_bus::round_trip(&client);
+
+				for sleeping in [true, false] {
+					service
+						.emit_signal(
+							None,
+							"/org/freedesktop/login1",
+							"org.freedesktop.login1.Manager",
+							"PrepareForSleep",
+							Some(&(sleeping,).to_variant()),
+						)
+						.unwrap();
+				}
+
+				properties_changed(
+					&service,
+					"/org/freedesktop/UPower",
+					"org.freedesktop.UPower",
+					"OnBattery",
+					true.to_variant(),
+				);
+
+				for profile in ["power-saver", "balanced", "performance"] {
+					properties_changed(
+						&service,
+						"/net/hadess/PowerProfiles",
+						"net.hadess.PowerProfiles",
+						"ActiveProfile",
+						profile.to_variant(),
+					);
+				}
+
+				// Sent after the others, so that no event is expected after it.
+				properties_changed(
+					&service,
+					"/org/freedesktop/UPower",
+					"org.freedesktop.UPower",
+					"OnBattery",
+					false.to_variant(),
+				);
+
+				let mut events = Vec::new();
+
+				test_bus::wait_for(&context, || {
+					events.extend(event_rx.try_iter().filter_map(|event| {
+						match event {
+							Event::Power(event) => Some(event),
+							_ => None,
+						}
+					}));
+
+					(events.last() == Some(&PowerEvent::PowerSourceChanged(PowerSource::Ac)))
+						.then_some(())
+				});
+
+				assert_eq!(events, [
+					PowerEvent::Suspending,
+					PowerEvent::Resumed,
+					PowerEvent::PowerSourceChanged(PowerSource::Battery),
+					PowerEvent::LowPowerModeChanged(true),
+					PowerEvent::LowPowerModeChanged(false),
+					PowerEvent::PowerSourceChanged(PowerSource::Ac),
+				]);
+			})
+			.unwrap();
+	}
+}
@@ -0,0 +1,220 @@
+use std::{ptr, time::Duration};
+
+use gtk::{
+	ApplicationInhibitFlags,
+	gdk,
+	gio::{self, BusType, Cancellable, DBusCallFlags, DBusConnection},
+	glib::{self, ToVariant, VariantTy},
+	prelude::*,
+};
+
+use crate::{
+	error::{ExternalError, NotSupportedError},
+	event::Event,
+	event_loop::InhibitFlags,
+};
+
+const SCREENSAVER_NAME:&str = "org.freedesktop.ScreenSaver";
+
+const SCREENSAVER_PATH:&str = "/org/freedesktop/ScreenSaver";
+
+/// Keeps an inhibition registered with the session manager until dropped.
+pub enum InhibitCookie {
+	Application { app:gtk::Application, cookie:u32 },
+	ScreenSaver { connection:DBusConnection, cookie:u32 },
+}
+
+impl Drop for InhibitCookie {
+	fn drop(&mut self) {
+		match self {
+			Self::Application { app, cookie } => app.uninhibit(*cookie),
+			Self::ScreenSaver { connection, cookie } => {
+				connection.call(
+					Some(SCREENSAVER_NAME),
+					SCREENSAVER_PATH,
+					SCREENSAVER_NAME,
+					"UnInhibit",
+					Some(&(*cookie,).to_variant()),
+					None,
+					DBusCallFlags::NONE,
+					-1,
+					None::<&Cancellable>,
+					|result| {
+						if let Err(e) = result {
+							log::warn!("Failed to uninhibit the screensaver: {}", e);
+						}
+					},
+				)
+			},
+		}
+	}
+}
+
+pub fn inhibit(
+	app:&gtk::Application,
+	flags:InhibitFlags,
+	reason:&str,
+) -> Result<InhibitCookie, ExternalError> {
+	let mut gtk_flags = ApplicationInhibitFlags::empty();
+
+	gtk_flags.set(ApplicationInhibitFlags::LOGOUT, flags.contains(InhibitFlags::LOGOUT));
+
+	gtk_flags.set(ApplicationInhibitFlags::SWITCH, flags.contains(InhibitFlags::SWITCH_USER));
+
+	gtk_flags.set(ApplicationInhibitFlags::SUSPEND, flags.contains(InhibitFlags::SUSPEND));
+
+	// A cookie of 0 means the session manager refused the request or isn't running.
+	match app.inhibit(None::<&gtk::Window>, gtk_flags, Some(reason)) {
+		0 => Err(ExternalError::Os(os_error!(super::OsError))),
+		cookie => Ok(InhibitCookie::Application { app:app.clone(), cookie }),
+	}
+}
+
+/// Prevents the screen from blanking while `window` is mapped.
+pub fn inhibit_idle(
+	window:&gtk::ApplicationWindow,
+	reason:&str,
+) -> Result<InhibitCookie, ExternalError> {
+	if let Some(app) = window.application() {
+		let cookie = app.inhibit(Some(window), ApplicationInhibitFlags::IDLE, Some(reason));
+		if cookie != 0 {
+			return Ok(InhibitCookie::Application { app, cookie });
+		}
+	}
+
+	// GTK only talks to the GNOME session manager and the portal, other desktops implement the
+	// screensaver interface.
+	let connection = session_bus()?;
+
+	let app_name = glib::application_name().or_else(glib::prgname).unwrap_or_else(|| "tao".into());
+
+	let reply = connection
+		.call_sync(
+			Some(SCREENSAVER_NAME),
+			SCREENSAVER_PATH,
+			SCREENSAVER_NAME,
+			"Inhibit",
+			Some(&(app_name.as_str(), reason).to_variant()),
+			VariantTy::new("(u)").ok(),
+			DBusCallFlags::NONE,
+			-1,
+			None::<&Cancellable>,
+		)
+		.map_err(|_| ExternalError::Os(os_error!(super::OsError)))?;
+
+	let (cookie,) = reply
+		.get::<(u32,)>()
+		.ok_or_else(|| ExternalError::Os(os_error!(super::OsError)))?;
+
+	Ok(InhibitCookie::ScreenSaver { connection, cookie })
+}
+
+/// Returns how long the user hasn't used any input device.
+pub fn user_idle_time(display:&gdk::Display) -> Result<Duration, ExternalError> {
+	if let Ok(connection) = session_bus() {
+		// Implemented by GNOME, on X11 and Wayland.
+		if let Some((idle,)) = call_for_reply::<(u64,)>(
+			&connection,
+			"org.gnome.Mutter.IdleMonitor",
+			"/org/gnome/Mutter/IdleMonitor/Core",
+			"GetIdletime",
+			"(t)",
+		) {
+			return Ok(Duration::from_millis(idle));
+		}
+
+		// Implemented by KDE, on X11 and Wayland.
+		if let Some((idle,)) = call_for_reply::<(u32,)>(
+			&connection,
+			SCREENSAVER_NAME,
+			SCREENSAVER_PATH,
+			"GetSessionIdleTime",
+			"(u)",
+		) {
+			return Ok(Duration::from_millis(idle as u64));
+		}
+	}
+
+	if display.backend().is_x11() {
+		return x11_idle_time().ok_or_else(|| ExternalError::Os(os_error!(super::OsError)));
+	}
+
+	Err(ExternalError::NotSupported(NotSupportedError::new()))
+}
+
+fn session_bus() -> Result<DBusConnection, ExternalError> {
+	gio::bus_get_sync(BusType::Session, None::<&Cancellable>)
+		.map_err(|_| ExternalError::Os(os_error!(super::OsError)))
+}
+
+fn call_for_reply<R:glib::FromVariant>(
+	connection:&DBusConnection,
+	name:&str,
+	path:&str,
+	method:&str,
+	reply_type:&str,
+) -> Option<R> {
+	connection
+		.call_sync(
+			Some(name),
+			path,
+			name,
+			method,
+			None,
+			VariantTy::new(reply_type).ok(),
+			DBusCallFlags::NONE,
+			-1,
+			None::<&Cancellable>,
+		)
+		.ok()?
+		.get()
+}
+
+fn x11_idle_time() -> Option<Duration> {
+	let xlib = x11_dl::xlib::Xlib::open().ok()?;
+
+	let xss = x11_dl::xss::Xss::open().ok()?;
+
+	unsafe {
+		let display = (xlib.XOpenDisplay)(ptr::null());
+		if display.is_null() {
+			return None;
+		}
+
+		let info = (xss.XScreenSaverAllocInfo)();
+
+		let idle = if !info.is_null()
+			&& (xss.XScreenSaverQueryInfo)(display, (xlib.XDefaultRootWindow)(display), info) != 0
+		{
+			Some(Duration::from_millis((*info).idle as u64))
+		} else {
+			None
+		};
+
+		if !info.is_null() {
+			(xlib.XFree)(info.cast());
+		}
+
+		(xlib.XCloseDisplay)(display);
+
+		idle
+	}
+}
+
+/// Sends `Event::SessionEnding` when the session manager asks whether the
+/// session may end.
+///
+/// This requires the application to be registered with the session manager,
+/// see `GtkApplication:register-session`.
+pub fn watch<T:'static>(
+	app:&gtk::Application,
+	event_tx:&crossbeam_channel::Sender<Event<'static, T>>,
+) {
+	let event_tx = event_tx.clone();
+
+	app.connect_query_end(move |_| {
+		if let Err(e) = event_tx.send(Event::SessionEnding) {
+			log::warn!("Failed to send session ending event to event channel: {}", e);
+		}
+	});
+}
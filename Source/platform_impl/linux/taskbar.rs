@@ -1,169 +1,292 @@
-use std::ffi::{CString, c_char};
+//! Taskbar progress and badges over the Unity `LauncherEntry` protocol.
+//!
+//! The state of the launcher entry is broadcast with
+//! `com.canonical.Unity.LauncherEntry.Update` signals on the session bus, which
+//! are understood by Unity, KDE Plasma, Dash-to-Dock and elementary. Docks
+//! started later ask for it with `Query`. A private `dbus-daemon` can stand in
+//! for the session bus with `DBUS_SESSION_BUS_ADDRESS`.
 
-use dlopen2::wrapper::{Container, WrapperApi};
+use std::{collections::HashMap, sync::Arc};
+
+use gtk::{
+	gio::{self, BusType, Cancellable, DBusConnection, DBusNodeInfo},
+	glib::{self, ToVariant},
+};
+use parking_lot::Mutex;
 
 use crate::window::{ProgressBarState, ProgressState};
 
-#[derive(WrapperApi)]
-struct UnityLib {
-	unity_launcher_entry_get_for_desktop_id:unsafe extern fn(id:*const c_char) -> *const isize,
-	unity_inspector_get_default:unsafe extern fn() -> *const isize,
-	unity_inspector_get_unity_running:unsafe extern fn(inspector:*const isize) -> i32,
-	unity_launcher_entry_set_progress:unsafe extern fn(entry:*const isize, value:f64) -> i32,
-	unity_launcher_entry_set_progress_visible:
-		unsafe extern fn(entry:*const isize, value:i32) -> i32,
-	unity_launcher_entry_set_count:unsafe extern fn(entry:*const isize, value:i64) -> i32,
-	unity_launcher_entry_set_count_visible:unsafe extern fn(entry:*const isize, value:bool) -> bool,
-}
+const INTERFACE:&str = "com.canonical.Unity.LauncherEntry";
 
-pub struct TaskbarIndicator {
+const INTROSPECTION:&str = r#"<node>
+	<interface name="com.canonical.Unity.LauncherEntry">
+		<signal name="Update">
+			<arg type="s" name="app_uri"/>
+			<arg type="a{sv}" name="properties"/>
+		</signal>
+		<method name="Query">
+			<arg type="s" name="app_uri" direction="out"/>
+			<arg type="a{sv}" name="properties" direction="out"/>
+		</method>
+	</interface>
+</node>"#;
+
+#[derive(Default)]
+struct LauncherEntry {
 	desktop_filename:Option<String>,
-	desktop_filename_c_str:Option<CString>,
+	progress:f64,
+	progress_visible:bool,
+	count:i64,
+	count_visible:bool,
+	urgent:bool,
+}
 
-	unity_lib:Option<Container<UnityLib>>,
-	attempted_load:bool,
+impl LauncherEntry {
+	/// Returns the `(app_uri, properties)` of the signal and of the reply to
+	/// `Query`, or `None` if the desktop file isn't known.
+	fn to_variant(&self) -> Option<glib::Variant> {
+		let app_uri = format!("application://{}", self.desktop_filename.as_ref()?);
 
-	unity_inspector:Option<*const isize>,
-	unity_entry:Option<*const isize>,
+		let properties:HashMap<String, glib::Variant> = HashMap::from([
+			("progress".into(), self.progress.to_variant()),
+			("progress-visible".into(), self.progress_visible.to_variant()),
+			("count".into(), self.count.to_variant()),
+			("count-visible".into(), self.count_visible.to_variant()),
+			("urgent".into(), self.urgent.to_variant()),
+		]);
+
+		Some((app_uri, properties).to_variant())
+	}
+}
+
+pub struct TaskbarIndicator {
+	entry:Arc<Mutex<LauncherEntry>>,
+
+	connection:Option<DBusConnection>,
+	attempted_connect:bool,
 }
 
 impl TaskbarIndicator {
-	pub fn new() -> Self {
-		Self {
-			desktop_filename:None,
-			desktop_filename_c_str:None,
+	/// The desktop file defaults to the one named after `app_id`, as
+	/// recommended by the desktop entry specification.
+	pub fn new(app_id:Option<&str>) -> Self {
+		let entry = LauncherEntry {
+			desktop_filename:app_id.map(|app_id| format!("{}.desktop", app_id)),
+			..Default::default()
+		};
 
-			unity_lib:None,
-			attempted_load:false,
+		Self { entry:Arc::new(Mutex::new(entry)), connection:None, attempted_connect:false }
+	}
 
-			unity_inspector:None,
-			unity_entry:None,
-		}
+	fn object_path() -> String {
+		format!("/com/canonical/unity/launcherentry/{}", std::process::id())
 	}
 
-	fn ensure_lib_load(&mut self) {
-		if self.attempted_load {
+	fn ensure_connection(&mut self) {
+		if self.attempted_connect {
 			return;
 		}
 
-		self.attempted_load = true;
+		self.attempted_connect = true;
 
-		self.unity_lib = unsafe {
-			Container::load("libunity.so.4")
-				.or_else(|_| Container::load("libunity.so.6"))
-				.or_else(|_| Container::load("libunity.so.9"))
-				.ok()
-		};
-
-		if let Some(unity_lib) = &self.unity_lib {
-			let handle = unsafe { unity_lib.unity_inspector_get_default() };
-			if !handle.is_null() {
-				self.unity_inspector = Some(handle);
-			}
+		match gio::bus_get_sync(BusType::Session, None::<&Cancellable>) {
+			Ok(connection) => self.set_connection(connection),
+			Err(e) => {
+				log::warn!("Failed to connect to the session bus, the taskbar entry is disabled: {}", e)
+			},
 		}
 	}
 
-	fn ensure_entry_load(&mut self) {
-		if let Some(unity_lib) = &self.unity_lib {
-			if let Some(id) = &self.desktop_filename_c_str {
-				let handle =
-					unsafe { unity_lib.unity_launcher_entry_get_for_desktop_id(id.as_ptr()) };
+	/// Serves `Query` and emits the updates on `connection`.
+	fn set_connection(&mut self, connection:DBusConnection) {
+		self.attempted_connect = true;
 
-				if !handle.is_null() {
-					self.unity_entry = Some(handle);
-				}
-			}
-		}
-	}
+		let interface = DBusNodeInfo::for_xml(INTROSPECTION)
+			.ok()
+			.and_then(|node| node.lookup_interface(INTERFACE))
+			.expect("Invalid LauncherEntry introspection data");
 
-	fn is_unity_running(&self) -> bool {
-		if let Some(inspector) = self.unity_inspector {
-			if let Some(unity_lib) = &self.unity_lib {
-				return unsafe { unity_lib.unity_inspector_get_unity_running(inspector) } == 1;
-			}
+		let entry = self.entry.clone();
+
+		if let Err(e) = connection.register_object(
+			&Self::object_path(),
+			&interface,
+			move |_, _, _, _, method, _, invocation| {
+				match (method, entry.lock().to_variant()) {
+					("Query", Some(reply)) => invocation.return_value(Some(&reply)),
+					("Query", None) => {
+						invocation.return_dbus_error(
+							"org.freedesktop.DBus.Error.Failed",
+							"The desktop file of the application is unknown",
+						)
+					},
+					_ => {
+						invocation.return_dbus_error(
+							"org.freedesktop.DBus.Error.UnknownMethod",
+							"Unknown method",
+						)
+					},
+				}
+			},
+			// The interface has no properties.
+			|_, _, _, _, _| ().to_variant(),
+			|_, _, _, _, _, _| false,
+		) {
+			log::warn!("Failed to register the LauncherEntry object: {}", e);
 		}
 
-		false
+		self.connection = Some(connection);
 	}
 
-	pub fn update(&mut self, progress:ProgressBarState) {
-		if let Some(uri) = progress.desktop_filename {
-			self.desktop_filename = Some(uri);
-		}
+	fn emit_update(&mut self) {
+		self.ensure_connection();
 
-		self.ensure_lib_load();
+		let Some(connection) = &self.connection else {
+			return;
+		};
+
+		let Some(parameters) = self.entry.lock().to_variant() else {
+			log::warn!("The taskbar entry can't be updated without the desktop filename");
 
-		if !self.is_unity_running() {
 			return;
-		}
+		};
 
-		if let Some(uri) = &self.desktop_filename {
-			self.desktop_filename_c_str = Some(CString::new(uri.as_str()).unwrap_or_default());
+		if let Err(e) =
+			connection.emit_signal(None, &Self::object_path(), INTERFACE, "Update", Some(&parameters))
+		{
+			log::warn!("Failed to emit the LauncherEntry update: {}", e);
 		}
+	}
 
-		if self.unity_entry.is_none() {
-			self.ensure_entry_load();
-		}
+	pub fn update(&mut self, progress:ProgressBarState) {
+		{
+			let mut entry = self.entry.lock();
 
-		if let Some(unity_lib) = &self.unity_lib {
-			if let Some(unity_entry) = &self.unity_entry {
-				if let Some(progress) = progress.progress {
-					let progress = if progress > 100 { 100 } else { progress };
-					let progress = progress as f64 / 100.0;
-					unsafe {
-						(unity_lib.unity_launcher_entry_set_progress)(*unity_entry, progress)
-					};
-				}
+			if let Some(desktop_filename) = progress.desktop_filename {
+				entry.desktop_filename = Some(desktop_filename);
+			}
 
-				if let Some(state) = progress.state {
-					let is_visible = !matches!(state, ProgressState::None);
-					unsafe {
-						(unity_lib.unity_launcher_entry_set_progress_visible)(
-							*unity_entry,
-							if is_visible { 1 } else { 0 },
-						)
-					};
-				}
+			if let Some(progress) = progress.progress {
+				entry.progress = progress.min(100) as f64 / 100.0;
+			}
+
+			if let Some(state) = progress.state {
+				// The protocol can't show a progress without a value, the bar is hidden instead.
+				entry.progress_visible =
+					!matches!(state, ProgressState::None | ProgressState::Indeterminate);
+
+				// The protocol has no progress states, an error asks for attention instead.
+				entry.urgent = matches!(state, ProgressState::Error);
 			}
 		}
+
+		self.emit_update();
 	}
 
 	pub fn update_count(&mut self, count:Option<i64>, desktop_filename:Option<String>) {
-		if let Some(uri) = desktop_filename {
-			self.desktop_filename = Some(uri);
-		}
+		{
+			let mut entry = self.entry.lock();
 
-		self.ensure_lib_load();
+			if let Some(desktop_filename) = desktop_filename {
+				entry.desktop_filename = Some(desktop_filename);
+			}
 
-		if !self.is_unity_running() {
-			return;
-		}
+			entry.count = count.unwrap_or(0);
 
-		if let Some(uri) = &self.desktop_filename {
-			self.desktop_filename_c_str = Some(CString::new(uri.as_str()).unwrap_or_default());
+			entry.count_visible = count.is_some();
 		}
 
-		if self.unity_entry.is_none() {
-			self.ensure_entry_load();
-		}
+		self.emit_update();
+	}
+}
 
-		if let Some(unity_lib) = &self.unity_lib {
-			if let Some(unity_entry) = &self.unity_entry {
-				// Sets count
-				if let Some(count) = count {
-					unsafe { (unity_lib.unity_launcher_entry_set_count)(*unity_entry, count) };
-					unsafe {
-						(unity_lib.unity_launcher_entry_set_count_visible)(*unity_entry, true)
-					};
-				}
-				// removes the count
-				else {
-					unsafe { (unity_lib.unity_launcher_entry_set_count)(*unity_entry, 0) };
-					unsafe {
-						(unity_lib.unity_launcher_entry_set_count_visible)(*unity_entry, false)
-					};
+#[cfg(test)]
+mod tests {
+	use std::{cell::RefCell, rc::Rc};
+
+	use gtk::gio::DBusSignalFlags;
+
+	use super::{
+		super::test_bus::{self, TestBus},
+		*,
+	};
+
+	type Update = (String, HashMap<String, glib::Variant>);
+
+	fn progress(state:ProgressState, progress:Option<u64>) -> ProgressBarState {
+		ProgressBarState { state:Some(state), progress, desktop_filename:None }
+	}
+
+	#[test]
+	fn emits_updates() {
+		let bus = TestBus::new();
+
+		let context = glib::MainContext::new();
+
+		context
+			.with_thread_default(|| {
+				let client = bus.connect(&[]);
+
+				let updates:Rc<RefCell<Vec<Update>>> = Rc::default();
+
+				let updates_ = updates.clone();
+				client.signal_subscribe(
+					None,
+					Some(INTERFACE),
+					Some("Update"),
+					None,
+					None,
+					DBusSignalFlags::NONE,
+					move |_, _, _, _, _, parameters| {
+						if let Some(update) = parameters.get::<Update>() {
+							updates_.borrow_mut().push(update);
+						}
+					},
+				);
+
+				test_bus::round_trip(&client);
+
+				let mut indicator = TaskbarIndicator::new(Some("org.example.App"));
+
+				indicator.set_connection(bus.connect(&[]));
+
+				indicator.update(progress(ProgressState::Normal, Some(42)));
+				indicator.update_count(Some(3), None);
+				indicator.update(progress(ProgressState::Error, None));
+				indicator.update(progress(ProgressState::Indeterminate, None));
+				indicator.update_count(None, None);
+
+				let updates = test_bus::wait_for(&context, || {
+					let received = updates.borrow().len() == 5;
+
+					received.then(|| updates.take())
+				});
+
+				for (app_uri, _) in &updates {
+					assert_eq!(app_uri, "application://org.example.App.desktop");
 				}
-			}
-		}
+
+				let states:Vec<_> = updates
+					.iter()
+					.map(|(_, properties)| {
+						(
+							properties["progress"].get::<f64>().unwrap(),
+							properties["progress-visible"].get::<bool>().unwrap(),
+							properties["count"].get::<i64>().unwrap(),
+							properties["count-visible"].get::<bool>().unwrap(),
+							properties["urgent"].get::<bool>().unwrap(),
+						)
+					})
+					.collect();
+
+				assert_eq!(states, [
+					(0.42, true, 0, false, false),
+					(0.42, true, 3, true, false),
+					(0.42, true, 3, true, true),
+					(0.42, false, 3, true, false),
+					(0.42, false, 0, false, false),
+				]);
+			})
+			.unwrap();
 	}
 }
@@ -0,0 +1,163 @@
+//! A private `dbus-daemon` for the tests of the D-Bus clients, on which mock
+//! services own the well-known names of the real ones.
+
+use std::{
+	io::{BufRead, BufReader},
+	process::{Child, Command, Stdio},
+	sync::mpsc,
+	thread::{self, JoinHandle},
+	time::{Duration, Instant},
+};
+
+use gtk::{
+	gio::{Cancellable, DBusCallFlags, DBusConnection, DBusConnectionFlags},
+	glib::{self, ToVariant},
+};
+
+pub struct TestBus {
+	daemon:Child,
+	address:String,
+}
+
+impl TestBus {
+	pub fn new() -> Self {
+		let mut daemon = Command::new("dbus-daemon")
+			.args(["--session", "--nofork", "--print-address"])
+			.stdout(Stdio::piped())
+			.spawn()
+			.expect("Failed to start dbus-daemon");
+
+		// The address is printed once the bus accepts connections.
+		let mut address = String::new();
+		BufReader::new(daemon.stdout.take().unwrap())
+			.read_line(&mut address)
+			.expect("Failed to read the address of dbus-daemon");
+
+		Self { daemon, address:address.trim().to_string() }
+	}
+
+	/// Opens a new connection to the bus, owning the well-known `names`.
+	pub fn connect(&self, names:&[&str]) -> DBusConnection { connect(&self.address, names) }
+
+	/// Opens a new connection owning the well-known `names` on a thread of its
+	/// own, which dispatches the method calls to the objects registered by
+	/// `register`. The code under test may then call them synchronously.
+	pub fn serve(
+		&self,
+		names:&[&str],
+		register:impl FnOnce(&DBusConnection) + Send + 'static,
+	) -> Service {
+		let address = self.address.clone();
+
+		let names:Vec<String> = names.iter().map(|name| name.to_string()).collect();
+
+		let (tx, rx) = mpsc::channel();
+
+		let thread = thread::spawn(move || {
+			let context = glib::MainContext::new();
+
+			context
+				.with_thread_default(|| {
+					let names:Vec<&str> = names.iter().map(String::as_str).collect();
+
+					let connection = connect(&address, &names);
+
+					register(&connection);
+
+					let main_loop = glib::MainLoop::new(Some(&context), false);
+
+					// Sent once the loop runs, so that it can't be quit before.
+					let main_loop_ = main_loop.clone();
+					glib::idle_add_local_once(move || {
+						let _ = tx.send((connection, main_loop_));
+					});
+
+					main_loop.run();
+				})
+				.unwrap();
+		});
+
+		let (connection, main_loop) = rx.recv().expect("Failed to start the service");
+
+		Service { connection, main_loop, thread:Some(thread) }
+	}
+}
+
+impl Drop for TestBus {
+	fn drop(&mut self) {
+		let _ = self.daemon.kill();
+		let _ = self.daemon.wait();
+	}
+}
+
+pub struct Service {
+	pub connection:DBusConnection,
+	main_loop:glib::MainLoop,
+	thread:Option<JoinHandle<()>>,
+}
+
+impl Drop for Service {
+	fn drop(&mut self) {
+		self.main_loop.quit();
+
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
+
+fn connect(address:&str, names:&[&str]) -> DBusConnection {
+	let connection = DBusConnection::for_address_sync(
+		address,
+		DBusConnectionFlags::AUTHENTICATION_CLIENT | DBusConnectionFlags::MESSAGE_BUS_CONNECTION,
+		None,
+		None::<&Cancellable>,
+	)
+	.expect("Failed to connect to dbus-daemon");
+
+	for name in names {
+		call_bus(&connection, "RequestName", &(*name, 0u32).to_variant());
+	}
+
+	connection
+}
+
+/// Waits until the bus has processed the messages sent on `connection` so far,
+/// such as the match rules of its signal subscriptions.
+pub fn round_trip(connection:&DBusConnection) {
+	call_bus(connection, "GetId", &().to_variant());
+}
+
+fn call_bus(connection:&DBusConnection, method:&str, parameters:&glib::Variant) {
+	connection
+		.call_sync(
+			Some("org.freedesktop.DBus"),
+			"/org/freedesktop/DBus",
+			"org.freedesktop.DBus",
+			method,
+			Some(parameters),
+			None,
+			DBusCallFlags::NONE,
+			-1,
+			None::<&Cancellable>,
+		)
+		.unwrap_or_else(|e| panic!("{} failed: {}", method, e));
+}
+
+/// Dispatches the sources of `context` until `f` returns `Some`, failing after
+/// a few seconds.
+pub fn wait_for<T>(context:&glib::MainContext, mut f:impl FnMut() -> Option<T>) -> T {
+	let deadline = Instant::now() + Duration::from_secs(5);
+
+	loop {
+		while context.iteration(false) {}
+
+		if let Some(value) = f() {
+			return value;
+		}
+
+		assert!(Instant::now() < deadline, "Timed out waiting for the bus");
+
+		thread::sleep(Duration::from_millis(10));
+	}
+}
@@ -1,19 +1,23 @@
 use std::{cell::RefCell, rc::Rc};
 
 use gtk::{
+	cairo::{Format, ImageSurface, RectangleInt, Region},
 	gdk::{
 		self,
 		Display,
-		prelude::{DeviceExt, SeatExt},
+		prelude::{DeviceExt, DisplayExtManual, GdkSurfaceExt, SeatExt},
 	},
-	glib::{self},
-	traits::{GtkWindowExt, WidgetExt},
+	gdk_pixbuf::Pixbuf,
+	glib::{self, prelude::ObjectExt, translate::ToGlibPtr},
+	traits::{GtkApplicationExt, GtkWindowExt, WidgetExt},
 };
 
 use crate::{
-	dpi::{LogicalPosition, LogicalSize, PhysicalPosition},
+	dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, PixelUnit},
 	error::ExternalError,
-	window::WindowSizeConstraints,
+	platform_impl::wayland::header::WlHeader,
+	platform::unix::WindowType,
+	window::{WindowPlacement, WindowRegion, WindowSizeConstraints, WindowStateFlags},
 };
 
 #[inline]
@@ -34,6 +38,164 @@ pub fn cursor_position(is_wayland:bool) -> Result<PhysicalPosition<f64>, Externa
 	}
 }
 
+/// Converts `region`, in physical pixels, to a region in the logical
+/// coordinates of a window with the given scale factor.
+pub fn cairo_region(region:&WindowRegion, scale_factor:i32) -> Option<Region> {
+	let physical = match region {
+		WindowRegion::Rectangles(rectangles) => {
+			let rectangles:Vec<RectangleInt> = rectangles
+				.iter()
+				.map(|(position, size)| {
+					RectangleInt::new(position.x, position.y, size.width as i32, size.height as i32)
+				})
+				.collect();
+
+			Region::create_rectangles(&rectangles)
+		},
+		WindowRegion::Mask(mask) if mask.width == 0 || mask.height == 0 => Region::create(),
+		WindowRegion::Mask(mask) => {
+			let width = mask.width as usize;
+
+			let mut surface =
+				ImageSurface::create(Format::A8, mask.width as i32, mask.height as i32).ok()?;
+
+			let stride = surface.stride() as usize;
+
+			{
+				let mut data = surface.data().ok()?;
+
+				for (row, alpha) in mask.alpha.chunks_exact(width).enumerate() {
+					data[row * stride..row * stride + width].copy_from_slice(alpha);
+				}
+			}
+
+			surface.create_region()?
+		},
+	};
+
+	if scale_factor == 1 {
+		return Some(physical);
+	}
+
+	// A logical pixel is covered as soon as one of its physical pixels is.
+	let logical = Region::create();
+
+	for i in 0..physical.num_rectangles() {
+		let rectangle = physical.rectangle(i);
+
+		let (left, top) =
+			(rectangle.x().div_euclid(scale_factor), rectangle.y().div_euclid(scale_factor));
+
+		let right = (rectangle.x() + rectangle.width() + scale_factor - 1).div_euclid(scale_factor);
+
+		let bottom =
+			(rectangle.y() + rectangle.height() + scale_factor - 1).div_euclid(scale_factor);
+
+		logical.union_rectangle(&RectangleInt::new(left, top, right - left, bottom - top)).ok()?;
+	}
+
+	Some(logical)
+}
+
+/// Returns the pixels of `pixbuf` as rows of 32bpp RGBA without padding, the
+/// layout of `Icon::from_rgba`.
+pub fn pixbuf_rgba(pixbuf:&Pixbuf) -> Option<(Vec<u8>, PhysicalSize<u32>)> {
+	let pixbuf = pixbuf.add_alpha(false, 0, 0, 0).ok()?;
+
+	let (width, height) = (pixbuf.width() as usize, pixbuf.height() as usize);
+
+	let bytes = pixbuf.read_pixel_bytes();
+
+	// The last row isn't padded to the rowstride.
+	let rgba = bytes
+		.chunks(pixbuf.rowstride() as usize)
+		.take(height)
+		.flat_map(|row| &row[..width * 4])
+		.copied()
+		.collect();
+
+	Some((rgba, PhysicalSize::new(width as u32, height as u32)))
+}
+
+/// The offset of a cascaded window from the previous one, before scaling.
+const CASCADE_OFFSET:i32 = 32;
+
+/// Places `window` before it is mapped. The window manager adds the
+/// decorations to the positions given to it.
+pub fn set_placement(window:&gtk::Window, placement:&WindowPlacement) {
+	match placement {
+		WindowPlacement::CenterOnMonitor(monitor) => {
+			let display = window.display();
+
+			let Some(monitor) = monitor
+				.as_ref()
+				.map(|monitor| monitor.inner.monitor.clone())
+				.or_else(|| display.primary_monitor())
+				.or_else(|| display.monitor(0))
+			else {
+				return;
+			};
+
+			let area = monitor.workarea();
+
+			// With the center gravity the window manager centers the window
+			// including its decorations on the position.
+			window.set_gravity(gdk::Gravity::Center);
+
+			window.move_(area.x() + area.width() / 2, area.y() + area.height() / 2);
+
+			// Later positions are of the top-left corner again.
+			let signal_id = Rc::new(RefCell::new(None));
+			let signal_id_ = signal_id.clone();
+			let id = window.connect_map_event(move |window, _| {
+				if let Some(id) = signal_id_.take() {
+					window.set_gravity(gdk::Gravity::NorthWest);
+					window.disconnect(id);
+				}
+
+				glib::Propagation::Proceed
+			});
+			signal_id.borrow_mut().replace(id);
+		},
+		WindowPlacement::CenterOnParent => window.set_position(gtk::WindowPosition::CenterOnParent),
+		WindowPlacement::AtCursor => window.set_position(gtk::WindowPosition::Mouse),
+		WindowPlacement::Cascade => {
+			let Some(app) = window.application() else {
+				return;
+			};
+
+			// The windows are sorted by when they were last focused.
+			let Some((origin, monitor)) = app
+				.windows()
+				.iter()
+				.filter(|other| *other != window && other.is_visible())
+				.filter_map(|other| other.window())
+				.find_map(|gdk_window| {
+					Some((gdk_window.root_origin(), gdk_window.display().monitor_at_window(&gdk_window)?))
+				})
+			else {
+				return;
+			};
+
+			let area = monitor.workarea();
+
+			let (width, height) = window.size();
+
+			let (mut x, mut y) = (origin.0 + CASCADE_OFFSET, origin.1 + CASCADE_OFFSET);
+
+			if x + width > area.x() + area.width() || y + height > area.y() + area.height() {
+				(x, y) = (area.x(), area.y());
+			}
+
+			window.move_(x, y);
+		},
+	}
+}
+
+/// The aspect ratio standing in for an unset one, as X11 expresses them as
+/// fractions of 16-bit sizes.
+const MAX_ASPECT:f64 = 65535.0;
+
 pub fn set_size_constraints<W:GtkWindowExt + WidgetExt>(
 	window:&W,
 	constraints:WindowSizeConstraints,
@@ -45,12 +207,31 @@ pub fn set_size_constraints<W:GtkWindowExt + WidgetExt>(
 	if constraints.has_max() {
 		geom_mask |= gdk::WindowHints::MAX_SIZE;
 	}
+	if constraints.has_base() {
+		geom_mask |= gdk::WindowHints::BASE_SIZE;
+	}
+	if constraints.has_increments() {
+		geom_mask |= gdk::WindowHints::RESIZE_INC;
+	}
+	if constraints.has_aspect() {
+		geom_mask |= gdk::WindowHints::ASPECT;
+	}
 
 	let scale_factor = window.scale_factor() as f64;
 
 	let min_size:LogicalSize<i32> = constraints.min_size_logical(scale_factor);
 	let max_size:LogicalSize<i32> = constraints.max_size_logical(scale_factor);
 
+	let base_size = constraints.base_size_physical::<f64>(scale_factor).to_logical::<i32>(scale_factor);
+
+	let increment = |increment:Option<PixelUnit>| {
+		increment.map_or(0, |increment| increment.to_logical::<i32>(scale_factor).0.max(1))
+	};
+
+	// GDK ignores the aspect ratios unless both are positive.
+	let min_aspect = constraints.min_aspect.filter(|aspect| *aspect > 0.0).unwrap_or(1.0 / MAX_ASPECT);
+	let max_aspect = constraints.max_aspect.filter(|aspect| *aspect > 0.0).unwrap_or(MAX_ASPECT);
+
 	let picky_none:Option<&gtk::Window> = None;
 	window.set_geometry_hints(
 		picky_none,
@@ -59,18 +240,108 @@ pub fn set_size_constraints<W:GtkWindowExt + WidgetExt>(
 			min_size.height,
 			max_size.width,
 			max_size.height,
-			0,
-			0,
-			0,
-			0,
-			0f64,
-			0f64,
+			base_size.width,
+			base_size.height,
+			increment(constraints.width_increment),
+			increment(constraints.height_increment),
+			min_aspect,
+			max_aspect,
 			gdk::Gravity::Center,
 		)),
 		geom_mask,
 	)
 }
 
+/// Applies the minimizable and maximizable state of the window, together with its resizable and
+/// closable state, to the window manager hints on X11 or the header bar buttons on Wayland.
+///
+/// GTK resets the X11 hints whenever the closable state changes, so this must be called again
+/// after that.
+pub fn set_functions(window:&gtk::Window, minimizable:bool, maximizable:bool) {
+	if window.display().backend().is_wayland() {
+		WlHeader::update_decoration_layout(window, minimizable, maximizable);
+	} else if let Some(gdk_window) = window.window() {
+		let resizable = window.is_resizable();
+
+		let mut functions = gdk::WMFunction::MOVE;
+		functions.set(gdk::WMFunction::RESIZE, resizable);
+		functions.set(gdk::WMFunction::MINIMIZE, minimizable);
+		functions.set(gdk::WMFunction::MAXIMIZE, maximizable && resizable);
+		functions.set(gdk::WMFunction::CLOSE, window.is_deletable());
+
+		gdk_window.set_functions(functions);
+	}
+}
+
+/// Converts a GDK window state into the flags reported by
+/// [`WindowEvent::StateChanged`](crate::event::WindowEvent::StateChanged).
+pub fn window_state_flags(state:gdk::WindowState) -> WindowStateFlags {
+	let mut flags = WindowStateFlags::empty();
+	flags.set(WindowStateFlags::MAXIMIZED, state.contains(gdk::WindowState::MAXIMIZED));
+	flags.set(WindowStateFlags::MINIMIZED, state.contains(gdk::WindowState::ICONIFIED));
+	flags.set(WindowStateFlags::FULLSCREEN, state.contains(gdk::WindowState::FULLSCREEN));
+	flags.set(WindowStateFlags::FOCUSED, state.contains(gdk::WindowState::FOCUSED));
+	flags.set(WindowStateFlags::TILED_TOP, state.contains(gdk::WindowState::TOP_TILED));
+	flags.set(WindowStateFlags::TILED_RIGHT, state.contains(gdk::WindowState::RIGHT_TILED));
+	flags.set(WindowStateFlags::TILED_BOTTOM, state.contains(gdk::WindowState::BOTTOM_TILED));
+	flags.set(WindowStateFlags::TILED_LEFT, state.contains(gdk::WindowState::LEFT_TILED));
+
+	// Window managers that predate per-edge tiling only report `TILED`, which means that every
+	// edge is constrained.
+	if state.contains(gdk::WindowState::TILED) && !flags.intersects(WindowStateFlags::TILED) {
+		flags |= WindowStateFlags::TILED;
+	}
+
+	flags
+}
+
+/// Sets the `WM_CLASS` of `window` on X11, or its application id on Wayland.
+///
+/// On X11 this has to happen before the window is realized.
+pub fn set_name(window:&gtk::Window, general:&str, instance:&str) {
+	if window.display().backend().is_wayland() {
+		// GTK creates the toplevel surface when the window is mapped, and a new
+		// one every time it is shown again.
+		let general = general.to_owned();
+		window.connect_map(move |window| {
+			if let Some(gdk_window) = window.window() {
+				unsafe {
+					gdk_wayland_sys::gdk_wayland_window_set_application_id(
+						gdk_window.to_glib_none().0 as *mut _,
+						general.to_glib_none().0,
+					);
+				}
+			}
+		});
+	} else {
+		#[allow(deprecated)]
+		unsafe {
+			gtk::ffi::gtk_window_set_wmclass(
+				window.to_glib_none().0,
+				instance.to_glib_none().0,
+				general.to_glib_none().0,
+			);
+		}
+	}
+}
+
+/// Converts a window type into the `_NET_WM_WINDOW_TYPE` hint of GDK.
+pub fn window_type_hint(window_type:WindowType) -> gdk::WindowTypeHint {
+	match window_type {
+		WindowType::Normal => gdk::WindowTypeHint::Normal,
+		WindowType::Dialog => gdk::WindowTypeHint::Dialog,
+		WindowType::Dock => gdk::WindowTypeHint::Dock,
+		WindowType::Toolbar => gdk::WindowTypeHint::Toolbar,
+		WindowType::Menu => gdk::WindowTypeHint::Menu,
+		WindowType::Utility => gdk::WindowTypeHint::Utility,
+		WindowType::Splash => gdk::WindowTypeHint::Splashscreen,
+		WindowType::DropdownMenu => gdk::WindowTypeHint::DropdownMenu,
+		WindowType::PopupMenu => gdk::WindowTypeHint::PopupMenu,
+		WindowType::Tooltip => gdk::WindowTypeHint::Tooltip,
+		WindowType::Notification => gdk::WindowTypeHint::Notification,
+	}
+}
+
 pub struct WindowMaximizeProcess<W:GtkWindowExt + WidgetExt> {
 	window:W,
 	resizable:bool,
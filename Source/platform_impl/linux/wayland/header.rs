@@ -1,9 +1,19 @@
+use std::{
+	rc::Rc,
+	sync::atomic::{AtomicBool, Ordering},
+};
+
 use gtk::{ApplicationWindow, EventBox, HeaderBar, prelude::*};
 
 pub struct WlHeader;
 
 impl WlHeader {
-	pub fn setup(window:&ApplicationWindow, title:&str) {
+	pub fn setup(
+		window:&ApplicationWindow,
+		title:&str,
+		minimizable:Rc<AtomicBool>,
+		maximizable:Rc<AtomicBool>,
+	) {
 		let header = HeaderBar::builder()
 			.show_close_button(true)
 			.decoration_layout("menu:minimize,maximize,close")
@@ -22,21 +32,67 @@ impl WlHeader {
 
 		window.set_titlebar(Some(&event_box));
 
-		Self::connect_resize_window(&header, window);
+		Self::connect_resize_window(&header, window, minimizable, maximizable);
+	}
+
+	/// Rebuilds the header bar buttons from the current capabilities of the window.
+	pub fn update_decoration_layout(
+		window:&gtk::Window,
+		minimizable:bool,
+		maximizable:bool,
+	) {
+		if let Some(header) = Self::header(window) {
+			header.set_decoration_layout(Some(&Self::decoration_layout(
+				window,
+				minimizable,
+				maximizable,
+			)));
+		}
+	}
+
+	fn header(window:&gtk::Window) -> Option<HeaderBar> {
+		window
+			.titlebar()?
+			.downcast::<EventBox>()
+			.ok()?
+			.child()?
+			.downcast::<HeaderBar>()
+			.ok()
+	}
+
+	fn decoration_layout(window:&gtk::Window, minimizable:bool, maximizable:bool) -> String {
+		let mut buttons = Vec::with_capacity(3);
+
+		if minimizable {
+			buttons.push("minimize");
+		}
+
+		if maximizable && window.is_resizable() {
+			buttons.push("maximize");
+		}
+
+		if window.is_deletable() {
+			buttons.push("close");
+		}
+
+		format!("menu:{}", buttons.join(","))
 	}
 
-	fn connect_resize_window(header:&HeaderBar, window:&ApplicationWindow) {
+	fn connect_resize_window(
+		header:&HeaderBar,
+		window:&ApplicationWindow,
+		minimizable:Rc<AtomicBool>,
+		maximizable:Rc<AtomicBool>,
+	) {
 		let header_weak = header.downgrade();
 
 		window.connect_resizable_notify(move |window| {
 			if let Some(header) = header_weak.upgrade() {
-				let is_resizable = window.is_resizable();
-
-				header.set_decoration_layout(if !is_resizable {
-					Some("menu:minimize,close")
-				} else {
-					Some("menu:minimize,maximize,close")
-				});
+				header.set_decoration_layout(Some(&Self::decoration_layout(
+					window.upcast_ref(),
+					minimizable.load(Ordering::Acquire),
+					maximizable.load(Ordering::Acquire),
+				)));
 			}
 		});
 	}
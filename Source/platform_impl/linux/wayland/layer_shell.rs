@@ -0,0 +1,105 @@
+use gtk::prelude::*;
+use gtk_layer_shell::{Edge, KeyboardMode, LayerShell};
+
+use crate::platform::unix::{Anchor, KeyboardInteractivity, Layer};
+
+/// The layer-shell options of a window, set with `WindowBuilderExtUnix`.
+#[derive(Clone, Default)]
+pub struct LayerShellAttributes {
+	/// The window is only turned into a layer surface if a layer is set.
+	pub layer:Option<Layer>,
+	pub anchor:Anchor,
+	pub margin:(i32, i32, i32, i32),
+	pub exclusive_zone:i32,
+	pub keyboard_interactivity:KeyboardInteractivity,
+	pub output:Option<gtk::gdk::Monitor>,
+}
+
+pub enum LayerShellRequest {
+	Layer(Layer),
+	Anchor(Anchor),
+	Margin(i32, i32, i32, i32),
+	ExclusiveZone(i32),
+	KeyboardInteractivity(KeyboardInteractivity),
+	Output(gtk::gdk::Monitor),
+}
+
+/// Turns `window` into a layer surface. Has to be called before the window is
+/// realized.
+///
+/// Returns `false` if the compositor doesn't support the layer-shell protocol.
+pub fn init(window:&gtk::ApplicationWindow, attributes:&LayerShellAttributes) -> bool {
+	let Some(layer) = attributes.layer else {
+		return false;
+	};
+
+	if !gtk_layer_shell::is_supported() {
+		log::warn!("The compositor doesn't support the layer-shell protocol");
+
+		return false;
+	}
+
+	window.init_layer_shell();
+
+	let window = window.upcast_ref::<gtk::Window>();
+
+	apply(window, LayerShellRequest::Layer(layer));
+
+	apply(window, LayerShellRequest::Anchor(attributes.anchor));
+
+	let (top, right, bottom, left) = attributes.margin;
+
+	apply(window, LayerShellRequest::Margin(top, right, bottom, left));
+
+	apply(window, LayerShellRequest::ExclusiveZone(attributes.exclusive_zone));
+
+	apply(window, LayerShellRequest::KeyboardInteractivity(attributes.keyboard_interactivity));
+
+	if let Some(output) = attributes.output.clone() {
+		apply(window, LayerShellRequest::Output(output));
+	}
+
+	true
+}
+
+pub fn is_layer_window(window:&impl IsA<gtk::Window>) -> bool { window.is_layer_window() }
+
+pub fn apply(window:&gtk::Window, request:LayerShellRequest) {
+	match request {
+		LayerShellRequest::Layer(layer) => {
+			window.set_layer(match layer {
+				Layer::Background => gtk_layer_shell::Layer::Background,
+				Layer::Bottom => gtk_layer_shell::Layer::Bottom,
+				Layer::Top => gtk_layer_shell::Layer::Top,
+				Layer::Overlay => gtk_layer_shell::Layer::Overlay,
+			})
+		},
+		LayerShellRequest::Anchor(anchor) => {
+			window.set_anchor(Edge::Top, anchor.contains(Anchor::TOP));
+
+			window.set_anchor(Edge::Right, anchor.contains(Anchor::RIGHT));
+
+			window.set_anchor(Edge::Bottom, anchor.contains(Anchor::BOTTOM));
+
+			window.set_anchor(Edge::Left, anchor.contains(Anchor::LEFT));
+		},
+		LayerShellRequest::Margin(top, right, bottom, left) => {
+			window.set_layer_shell_margin(Edge::Top, top);
+
+			window.set_layer_shell_margin(Edge::Right, right);
+
+			window.set_layer_shell_margin(Edge::Bottom, bottom);
+
+			window.set_layer_shell_margin(Edge::Left, left);
+		},
+		LayerShellRequest::ExclusiveZone(zone) => window.set_exclusive_zone(zone),
+		LayerShellRequest::KeyboardInteractivity(interactivity) => {
+			window.set_keyboard_mode(match interactivity {
+				KeyboardInteractivity::None => KeyboardMode::None,
+				KeyboardInteractivity::Exclusive => KeyboardMode::Exclusive,
+				KeyboardInteractivity::OnDemand => KeyboardMode::OnDemand,
+			})
+		},
+		LayerShellRequest::Output(monitor) => window.set_monitor(&monitor),
+	}
+}
@@ -3,3 +3,5 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod header;
+#[cfg(feature = "layer-shell")]
+pub mod layer_shell;
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-	cell::RefCell,
+	cell::{Cell, RefCell},
 	collections::VecDeque,
 	rc::Rc,
 	sync::{
@@ -15,7 +15,7 @@ use std::{
 use gtk::{
 	CssProvider,
 	Settings,
-	gdk::WindowState,
+	gdk::{self, WindowState},
 	glib::{self, translate::ToGlibPtr},
 	prelude::*,
 };
@@ -24,9 +24,13 @@ use super::{
 	Parent,
 	PlatformSpecificWindowBuilderAttributes,
 	event_loop::EventLoopWindowTarget,
+	hit_test::HitTester,
 	monitor::{self, MonitorHandle},
+	session,
 	util,
 };
+#[cfg(feature = "layer-shell")]
+use super::wayland::layer_shell::{self, LayerShellRequest};
 use crate::{
 	dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
 	error::{ExternalError, NotSupportedError, OsError as RootOsError},
@@ -35,13 +39,17 @@ use crate::{
 	platform_impl::wayland::header::WlHeader,
 	window::{
 		CursorIcon,
+		DragActions,
+		DragItem,
 		Fullscreen,
+		HitTest,
 		ProgressBarState,
 		RGBA,
 		ResizeDirection,
 		Theme,
 		UserAttentionType,
 		WindowAttributes,
+		WindowRegion,
 		WindowSizeConstraints,
 	},
 };
@@ -70,15 +78,23 @@ pub struct Window {
 	outer_position:Rc<(AtomicI32, AtomicI32)>,
 	outer_size:Rc<(AtomicI32, AtomicI32)>,
 	inner_size:Rc<(AtomicI32, AtomicI32)>,
+	/// Outer position and inner size while neither maximized nor fullscreen.
+	normal_geometry:Rc<Cell<((i32, i32), (i32, i32))>>,
 	maximized:Rc<AtomicBool>,
 	is_always_on_top:Rc<AtomicBool>,
 	minimized:Rc<AtomicBool>,
+	minimizable:Rc<AtomicBool>,
+	maximizable:Rc<AtomicBool>,
 	fullscreen:RefCell<Option<Fullscreen>>,
+	opacity:Cell<f64>,
+	input_region:RefCell<Option<WindowRegion>>,
+	shape:RefCell<Option<WindowRegion>>,
 	inner_size_constraints:RefCell<WindowSizeConstraints>,
 	/// Draw event Sender
 	draw_tx:crossbeam_channel::Sender<WindowId>,
 	preferred_theme:RefCell<Option<Theme>>,
 	css_provider:CssProvider,
+	hit_tester:Rc<HitTester>,
 }
 
 impl Window {
@@ -97,18 +113,72 @@ impl Window {
 
 		let mut window_builder = gtk::ApplicationWindow::builder()
 			.application(app)
-			.accept_focus(attributes.focused);
+			.accept_focus(attributes.focused)
+			.modal(attributes.modal);
 
 		if let Parent::ChildOf(parent) = pl_attribs.parent {
-			window_builder = window_builder.transient_for(&parent);
+			window_builder = window_builder
+				.transient_for(&parent)
+				.destroy_with_parent(pl_attribs.destroy_with_parent);
 		}
 
 		let window = window_builder.build();
 
-		if is_wayland {
-			WlHeader::setup(&window, &attributes.title);
+		// Layer surfaces are placed and decorated by the compositor. Without layer-shell support
+		// the window fails to build, as an ordinary window can't be told apart from one.
+		#[cfg(feature = "layer-shell")]
+		let is_layer_surface = pl_attribs.layer_shell.layer.is_some();
+		#[cfg(feature = "layer-shell")]
+		if is_layer_surface && !(is_wayland && layer_shell::init(&window, &pl_attribs.layer_shell)) {
+			unsafe {
+				window.destroy();
+			}
+
+			return Err(os_error!(super::OsError));
+		}
+		#[cfg(not(feature = "layer-shell"))]
+		let is_layer_surface = false;
+
+		// The window manager reads these hints when the window is mapped.
+		window.set_type_hint(util::window_type_hint(pl_attribs.window_type));
+
+		window.set_skip_pager_hint(pl_attribs.skip_pager);
+
+		window.set_urgency_hint(pl_attribs.demands_attention);
+
+		if let Some((general, instance)) = &pl_attribs.name {
+			util::set_name(window.upcast_ref(), general, instance);
+		}
+
+		if let Some(role) = &pl_attribs.role {
+			window.set_role(role);
+		}
+
+		// The first window takes the activation token the application was launched with, so that
+		// it is allowed to take focus.
+		if let Some(token) = event_loop_window_target.startup_activation_token.take() {
+			window.set_startup_id(&token);
+		}
+
+		let minimizable = Rc::new(AtomicBool::new(attributes.minimizable));
+
+		let maximizable = Rc::new(AtomicBool::new(attributes.maximizable));
+
+		if is_wayland && !is_layer_surface {
+			WlHeader::setup(&window, &attributes.title, minimizable.clone(), maximizable.clone());
 		}
 
+		// GTK sets its own window manager hints when the window is realized, so ours have to be
+		// applied after that.
+		let (minimizable_, maximizable_) = (minimizable.clone(), maximizable.clone());
+		window.connect_realize(move |window| {
+			util::set_functions(
+				window.upcast_ref(),
+				minimizable_.load(Ordering::Acquire),
+				maximizable_.load(Ordering::Acquire),
+			);
+		});
+
 		let window_id = WindowId(window.id());
 
 		event_loop_window_target.windows.borrow_mut().insert(window_id);
@@ -143,7 +213,9 @@ impl Window {
 		util::set_size_constraints(&window, attributes.inner_size_constraints);
 
 		// Set Position
-		if let Some(position) = attributes.position {
+		if let Some(placement) = &attributes.placement {
+			util::set_placement(window.upcast_ref(), placement);
+		} else if let Some(position) = attributes.position {
 			let (x, y):(i32, i32) = position.to_logical::<i32>(win_scale_factor as f64).into();
 			window.move_(x, y);
 		}
@@ -221,6 +293,12 @@ impl Window {
 			window.stick();
 		}
 
+		let opacity = attributes.opacity.clamp(0.0, 1.0);
+
+		if opacity < 1.0 {
+			window.set_opacity(opacity);
+		}
+
 		let preferred_theme = if let Some(settings) = Settings::default() {
 			if let Some(preferred_theme) = attributes.preferred_theme {
 				match preferred_theme {
@@ -276,12 +354,15 @@ impl Window {
 
 		let cursor_moved = pl_attribs.cursor_moved;
 
+		let hit_tester = Rc::new(HitTester::default());
+
 		if let Err(e) = window_requests_tx.send((
 			window_id,
 			WindowRequest::WireUpEvents {
 				transparent,
 				fullscreen:attributes.fullscreen.is_some(),
 				cursor_moved,
+				hit_tester:hit_tester.clone(),
 			},
 		)) {
 			log::warn!("Fail to send wire up events request: {}", e);
@@ -298,6 +379,8 @@ impl Window {
 			is_always_on_top,
 		) = Self::setup_signals(&window, Some(&attributes));
 
+		let normal_geometry = Self::track_normal_geometry(&window);
+
 		if let Some(icon) = attributes.window_icon {
 			window.set_icon(Some(&icon.inner.into()));
 		}
@@ -313,16 +396,26 @@ impl Window {
 			inner_position,
 			outer_size,
 			inner_size,
+			normal_geometry,
 			maximized,
 			minimized,
+			minimizable,
+			maximizable,
 			is_always_on_top,
 			fullscreen:RefCell::new(attributes.fullscreen),
+			opacity:Cell::new(opacity),
+			input_region:RefCell::new(None),
+			shape:RefCell::new(None),
 			inner_size_constraints:RefCell::new(attributes.inner_size_constraints),
 			preferred_theme:RefCell::new(preferred_theme),
 			css_provider:CssProvider::new(),
+			hit_tester,
 		};
 
-		let _ = win.set_skip_taskbar(pl_attribs.skip_taskbar);
+		// Skipping the taskbar also skips the pager, so only override the hints when asked to.
+		if pl_attribs.skip_taskbar {
+			let _ = win.set_skip_taskbar(true);
+		}
 
 		win.set_background_color(attributes.background_color);
 
@@ -437,6 +530,40 @@ impl Window {
 		)
 	}
 
+	/// Keeps the outer position and inner size of the window while it is neither
+	/// maximized nor fullscreen, so that they can be restored.
+	fn track_normal_geometry(
+		window:&gtk::ApplicationWindow,
+	) -> Rc<Cell<((i32, i32), (i32, i32))>> {
+		let position = window.window().map(|w| w.root_origin()).unwrap_or_else(|| window.position());
+
+		let normal_geometry = Rc::new(Cell::new((position, window.size())));
+
+		let normal_geometry_clone = normal_geometry.clone();
+
+		window.connect_configure_event(move |window, event| {
+			let state = window.window().map(|w| w.state()).unwrap_or_else(WindowState::empty);
+
+			if !state.intersects(WindowState::MAXIMIZED | WindowState::FULLSCREEN) {
+				let (w, h) = event.size();
+
+				let position = window
+					.window()
+					.map(|w| {
+						let rect = w.frame_extents();
+						(rect.x(), rect.y())
+					})
+					.unwrap_or_else(|| event.position());
+
+				normal_geometry_clone.set((position, (w as i32, h as i32)));
+			}
+
+			false
+		});
+
+		normal_geometry
+	}
+
 	pub(crate) fn new_from_gtk_window<T>(
 		event_loop_window_target:&EventLoopWindowTarget<T>,
 		window:gtk::ApplicationWindow,
@@ -460,6 +587,10 @@ impl Window {
 			is_always_on_top,
 		) = Self::setup_signals(&window, None);
 
+		let normal_geometry = Self::track_normal_geometry(&window);
+
+		let opacity = window.opacity();
+
 		let win = Self {
 			window_id,
 			window,
@@ -471,13 +602,21 @@ impl Window {
 			inner_position,
 			outer_size,
 			inner_size,
+			normal_geometry,
 			maximized,
 			minimized,
+			minimizable:Rc::new(AtomicBool::new(true)),
+			maximizable:Rc::new(AtomicBool::new(true)),
 			is_always_on_top,
 			fullscreen:RefCell::new(None),
+			opacity:Cell::new(opacity),
+			input_region:RefCell::new(None),
+			shape:RefCell::new(None),
 			inner_size_constraints:RefCell::new(WindowSizeConstraints::default()),
 			preferred_theme:RefCell::new(None),
 			css_provider:CssProvider::new(),
+			// The events of the window aren't wired up, so the hit test is never used.
+			hit_tester:Rc::default(),
 		};
 
 		Ok(win)
@@ -507,6 +646,19 @@ impl Window {
 			.to_physical(self.scale_factor.load(Ordering::Acquire) as f64))
 	}
 
+	/// Returns the outer position and inner size the window had when it was last
+	/// neither maximized nor fullscreen. The position is `None` on Wayland.
+	pub fn normal_geometry(&self) -> (Option<PhysicalPosition<i32>>, PhysicalSize<u32>) {
+		let ((x, y), (width, height)) = self.normal_geometry.get();
+
+		let scale_factor = self.scale_factor();
+
+		let position = (!self.is_wayland())
+			.then(|| LogicalPosition::new(x, y).to_physical(scale_factor));
+
+		(position, LogicalSize::new(width as u32, height as u32).to_physical(scale_factor))
+	}
+
 	pub fn set_outer_position<P:Into<Position>>(&self, position:P) {
 		let (x, y):(i32, i32) = position.into().to_logical::<i32>(self.scale_factor()).into();
 
@@ -526,6 +678,54 @@ impl Window {
 		}
 	}
 
+	pub fn set_opacity(&self, opacity:f64) {
+		let opacity = opacity.clamp(0.0, 1.0);
+
+		self.opacity.set(opacity);
+
+		if let Err(e) = self.window_requests_tx.send((self.window_id, WindowRequest::Opacity(opacity))) {
+			log::warn!("Fail to send opacity request: {}", e);
+		}
+	}
+
+	pub fn opacity(&self) -> f64 { self.opacity.get() }
+
+	pub fn capture(
+		&self,
+		include_decorations:bool,
+	) -> Result<(Vec<u8>, PhysicalSize<u32>), ExternalError> {
+		let Some(window) = self.window.window().filter(|window| window.is_viewable()) else {
+			return Err(ExternalError::Os(os_error!(super::OsError)));
+		};
+
+		let frame = window.frame_extents();
+
+		let pixbuf = if !include_decorations {
+			let allocation = self
+				.window
+				.child()
+				.map(|child| child.allocation())
+				.unwrap_or_else(|| gtk::Allocation::new(0, 0, window.width(), window.height()));
+
+			window.pixbuf(allocation.x(), allocation.y(), allocation.width(), allocation.height())
+		} else if frame.width() > window.width() || frame.height() > window.height() {
+			// The decorations are drawn by the window manager around the window.
+			gdk::Window::default_root_window().pixbuf(
+				frame.x(),
+				frame.y(),
+				frame.width(),
+				frame.height(),
+			)
+		} else {
+			window.pixbuf(0, 0, window.width(), window.height())
+		};
+
+		pixbuf
+			.as_ref()
+			.and_then(util::pixbuf_rgba)
+			.ok_or_else(|| ExternalError::Os(os_error!(super::OsError)))
+	}
+
 	pub fn inner_size(&self) -> PhysicalSize<u32> {
 		let (width, height) = &*self.inner_size;
 
@@ -625,8 +825,20 @@ impl Window {
 		}
 	}
 
+	pub fn focus_with_activation_token(&self, token:String) {
+		if let Err(e) =
+			self.window_requests_tx.send((self.window_id, WindowRequest::FocusWithToken(token)))
+		{
+			log::warn!("Fail to send focus request: {}", e);
+		}
+	}
+
 	pub fn is_focused(&self) -> bool { self.window.is_active() }
 
+	pub fn inhibit_idle(&self, reason:&str) -> Result<session::InhibitCookie, ExternalError> {
+		session::inhibit_idle(&self.window, reason)
+	}
+
 	pub fn set_resizable(&self, resizable:bool) {
 		if let Err(e) = self
 			.window_requests_tx
@@ -634,11 +846,21 @@ impl Window {
 		{
 			log::warn!("Fail to send resizable request: {}", e);
 		}
+
+		self.update_functions();
+	}
+
+	pub fn set_minimizable(&self, minimizable:bool) {
+		self.minimizable.store(minimizable, Ordering::Release);
+
+		self.update_functions();
 	}
 
-	pub fn set_minimizable(&self, _minimizable:bool) {}
+	pub fn set_maximizable(&self, maximizable:bool) {
+		self.maximizable.store(maximizable, Ordering::Release);
 
-	pub fn set_maximizable(&self, _maximizable:bool) {}
+		self.update_functions();
+	}
 
 	pub fn set_closable(&self, closable:bool) {
 		if let Err(e) = self
@@ -647,6 +869,20 @@ impl Window {
 		{
 			log::warn!("Fail to send closable request: {}", e);
 		}
+
+		self.update_functions();
+	}
+
+	fn update_functions(&self) {
+		if let Err(e) = self.window_requests_tx.send((
+			self.window_id,
+			WindowRequest::Functions {
+				minimizable:self.minimizable.load(Ordering::Acquire),
+				maximizable:self.maximizable.load(Ordering::Acquire),
+			},
+		)) {
+			log::warn!("Fail to send functions request: {}", e);
+		}
 	}
 
 	pub fn set_minimized(&self, minimized:bool) {
@@ -677,9 +913,11 @@ impl Window {
 
 	pub fn is_resizable(&self) -> bool { self.window.is_resizable() }
 
-	pub fn is_minimizable(&self) -> bool { true }
+	pub fn is_minimizable(&self) -> bool { self.minimizable.load(Ordering::Acquire) }
 
-	pub fn is_maximizable(&self) -> bool { true }
+	pub fn is_maximizable(&self) -> bool {
+		self.maximizable.load(Ordering::Acquire) && self.is_resizable()
+	}
 
 	pub fn is_closable(&self) -> bool { self.window.is_deletable() }
 
@@ -707,6 +945,41 @@ impl Window {
 		Ok(())
 	}
 
+	pub fn set_hit_test(&self, hit_test:Option<HitTest>) { self.hit_tester.set_hit_test(hit_test); }
+
+	pub fn set_resize_border(&self, width:u32) { self.hit_tester.set_resize_border(width); }
+
+	pub fn start_drag(
+		&self,
+		items:Vec<DragItem>,
+		allowed_actions:DragActions,
+		icon:Option<Icon>,
+	) -> Result<(), ExternalError> {
+		if let Err(e) = self
+			.window_requests_tx
+			.send((self.window_id, WindowRequest::StartDrag { items, allowed_actions, icon }))
+		{
+			log::warn!("Fail to send start drag request: {}", e);
+		}
+
+		Ok(())
+	}
+
+	#[cfg(feature = "layer-shell")]
+	pub fn set_layer_shell(&self, request:LayerShellRequest) -> Result<(), ExternalError> {
+		if !layer_shell::is_layer_window(&self.window) {
+			return Err(ExternalError::NotSupported(NotSupportedError::new()));
+		}
+
+		if let Err(e) =
+			self.window_requests_tx.send((self.window_id, WindowRequest::LayerShell(request)))
+		{
+			log::warn!("Fail to send layer shell request: {}", e);
+		}
+
+		Ok(())
+	}
+
 	pub fn set_fullscreen(&self, fullscreen:Option<Fullscreen>) {
 		self.fullscreen.replace(fullscreen.clone());
 
@@ -815,6 +1088,40 @@ impl Window {
 		Ok(())
 	}
 
+	pub fn set_input_region(&self, region:Option<WindowRegion>) -> Result<(), ExternalError> {
+		self.input_region.replace(region);
+
+		self.send_input_region();
+
+		Ok(())
+	}
+
+	pub fn set_shape(&self, shape:Option<WindowRegion>) -> Result<(), ExternalError> {
+		self.shape.replace(shape.clone());
+
+		if let Err(e) = self.window_requests_tx.send((self.window_id, WindowRequest::Shape(shape))) {
+			log::warn!("Fail to send shape request: {}", e);
+		}
+
+		// Wayland has no window shapes, the shape limits the input region instead.
+		if self.is_wayland() {
+			self.send_input_region();
+		}
+
+		Ok(())
+	}
+
+	fn send_input_region(&self) {
+		let (region, shape) = (self.input_region.borrow().clone(), self.shape.borrow().clone());
+
+		if let Err(e) = self
+			.window_requests_tx
+			.send((self.window_id, WindowRequest::InputRegion { region, shape }))
+		{
+			log::warn!("Fail to send input region request: {}", e);
+		}
+	}
+
 	pub fn set_cursor_visible(&self, visible:bool) {
 		let cursor = if visible { Some(CursorIcon::Default) } else { None };
 
@@ -1075,12 +1382,15 @@ pub enum WindowRequest {
 	SizeConstraints(WindowSizeConstraints),
 	Visible(bool),
 	Focus,
+	FocusWithToken(String),
 	Resizable(bool),
 	Closable(bool),
+	Functions { minimizable:bool, maximizable:bool },
 	Minimized(bool),
 	Maximized(bool, bool),
 	DragWindow,
 	DragResizeWindow(ResizeDirection),
+	StartDrag { items:Vec<DragItem>, allowed_actions:DragActions, icon:Option<Icon> },
 	Fullscreen(Option<Fullscreen>),
 	Decorations(bool),
 	AlwaysOnBottom(bool),
@@ -1091,12 +1401,18 @@ pub enum WindowRequest {
 	CursorIcon(Option<CursorIcon>),
 	CursorPosition((i32, i32)),
 	CursorIgnoreEvents(bool),
-	WireUpEvents { transparent:bool, fullscreen:bool, cursor_moved:bool },
+	/// The input region, limited to the shape on Wayland.
+	InputRegion { region:Option<WindowRegion>, shape:Option<WindowRegion> },
+	Shape(Option<WindowRegion>),
+	WireUpEvents { transparent:bool, fullscreen:bool, cursor_moved:bool, hit_tester:Rc<HitTester> },
 	SetVisibleOnAllWorkspaces(bool),
 	ProgressBarState(ProgressBarState),
 	BadgeCount(Option<i64>, Option<String>),
 	SetTheme(Option<Theme>),
 	BackgroundColor(CssProvider, Option<RGBA>),
+	Opacity(f64),
+	#[cfg(feature = "layer-shell")]
+	LayerShell(LayerShellRequest),
 }
 
 impl Drop for Window {
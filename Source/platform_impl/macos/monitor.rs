@@ -7,7 +7,7 @@ use std::{collections::VecDeque, fmt};
 use super::ffi::{self, id, nil, CGRectContainsPoint};
 use crate::{
   dpi::{PhysicalPosition, PhysicalSize},
-  monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
+  monitor::{MonitorHandle as RootMonitorHandle, SubpixelLayout, VideoMode as RootVideoMode},
 };
 use core_foundation::{
   array::{CFArrayGetCount, CFArrayGetValueAtIndex},
@@ -311,6 +311,39 @@ impl MonitorHandle {
     }
   }
 
+  pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+    let size = CGDisplay::new(self.0).screen_size();
+    if size.width > 0.0 && size.height > 0.0 {
+      Some((size.width.round() as u32, size.height.round() as u32))
+    } else {
+      None
+    }
+  }
+
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    // CGDisplayModeGetRefreshRate returns 0.0 for any display that isn't a CRT
+    CGDisplay::new(self.0)
+      .display_mode()
+      .map(|mode| mode.refresh_rate())
+      .filter(|refresh_rate| *refresh_rate > 0.0)
+      .map(|refresh_rate| (refresh_rate * 1000.0).round() as u32)
+  }
+
+  #[inline]
+  pub fn manufacturer(&self) -> Option<String> {
+    None
+  }
+
+  #[inline]
+  pub fn connector_name(&self) -> Option<String> {
+    None
+  }
+
+  #[inline]
+  pub fn subpixel_layout(&self) -> SubpixelLayout {
+    SubpixelLayout::Unknown
+  }
+
   pub(crate) fn ns_screen(&self) -> Option<Retained<NSScreen>> {
     // SAFETY: TODO.
     let mtm = unsafe { MainThreadMarker::new_unchecked() };
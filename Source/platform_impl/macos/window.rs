@@ -36,8 +36,8 @@ use crate::{
     set_badge_label, set_progress_indicator,
   },
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ResizeDirection, Theme, UserAttentionType,
-    WindowAttributes, WindowId as RootWindowId, WindowSizeConstraints,
+    CursorIcon, DragActions, DragItem, Fullscreen, ProgressBarState, ResizeDirection, Theme,
+    UserAttentionType, WindowAttributes, WindowId as RootWindowId, WindowSizeConstraints,
   },
 };
 use core_graphics::{
@@ -279,6 +279,12 @@ fn create_window(
         ns_window.setLevel(ffi::NSWindowLevel::BelowNormalWindowLevel as isize);
       }
 
+      // A sheet would be attached to the title bar of the parent, so a modal window is only
+      // kept above the other windows.
+      if attrs.modal {
+        ns_window.setLevel(ffi::NSWindowLevel::NSModalPanelWindowLevel as isize);
+      }
+
       if attrs.content_protection  {
         ns_window.setSharingType(NSWindowSharingType::None);
       }
@@ -909,6 +915,16 @@ impl UnownedWindow {
     Err(ExternalError::NotSupported(NotSupportedError::new()))
   }
 
+  #[inline]
+  pub fn start_drag(
+    &self,
+    _items: Vec<DragItem>,
+    _allowed_actions: DragActions,
+    _icon: Option<Icon>,
+  ) -> Result<(), ExternalError> {
+    Err(ExternalError::NotSupported(NotSupportedError::new()))
+  }
+
   #[inline]
   pub fn set_ignore_cursor_events(&self, ignore: bool) -> Result<(), ExternalError> {
     unsafe {
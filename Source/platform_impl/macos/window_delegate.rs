@@ -16,6 +16,7 @@ use objc2::{
 };
 use objc2_app_kit::{
   self as appkit, NSApplicationPresentationOptions, NSPasteboard, NSView, NSWindow,
+  NSWindowOcclusionState, NSWindowStyleMask,
 };
 use objc2_foundation::{NSArray, NSAutoreleasePool, NSString, NSUInteger};
 
@@ -31,7 +32,7 @@ use crate::{
     view::ViewState,
     window::{get_ns_theme, get_window_id, UnownedWindow},
   },
-  window::{Fullscreen, WindowId},
+  window::{Fullscreen, WindowId, WindowStateFlags},
 };
 
 pub struct WindowDelegateState {
@@ -58,6 +59,9 @@ pub struct WindowDelegateState {
   // Used to prevent resized events from being fired
   // when we are using our workaround in the `is_zoomed` function.
   is_checking_zoomed_in: bool,
+
+  // Used to only send `StateChanged` when the state actually changed.
+  previous_state: WindowStateFlags,
 }
 
 impl WindowDelegateState {
@@ -71,7 +75,9 @@ impl WindowDelegateState {
       previous_position: None,
       previous_scale_factor: scale_factor,
       is_checking_zoomed_in: false,
+      previous_state: WindowStateFlags::empty(),
     };
+    delegate_state.previous_state = delegate_state.current_state();
     if (scale_factor - 1.0).abs() > f64::EPSILON {
       delegate_state.emit_static_scale_factor_changed_event();
     }
@@ -134,6 +140,33 @@ impl WindowDelegateState {
     }
   }
 
+  fn current_state(&self) -> WindowStateFlags {
+    let mut state = WindowStateFlags::empty();
+    unsafe {
+      // `UnownedWindow::is_zoomed` temporarily changes the style mask, which would re-enter
+      // this delegate, so we query `isZoomed` directly.
+      state.set(WindowStateFlags::MAXIMIZED, self.ns_window.isZoomed());
+      state.set(WindowStateFlags::MINIMIZED, self.ns_window.isMiniaturized());
+      state.set(
+        WindowStateFlags::FULLSCREEN,
+        self
+          .ns_window
+          .styleMask()
+          .contains(NSWindowStyleMask::FullScreen),
+      );
+      state.set(WindowStateFlags::FOCUSED, self.ns_window.isKeyWindow());
+    }
+    state
+  }
+
+  pub fn emit_state_changed_event(&mut self) {
+    let state = self.current_state();
+    if state != self.previous_state {
+      self.previous_state = state;
+      self.emit_event(WindowEvent::StateChanged(state));
+    }
+  }
+
   fn get_scale_factor(&self) -> f64 {
     NSWindow::backingScaleFactor(&self.ns_window) as f64
   }
@@ -197,6 +230,18 @@ lazy_static! {
       sel!(windowDidMove:),
       window_did_move as extern "C" fn(_, _, _),
     );
+    decl.add_method(
+      sel!(windowDidMiniaturize:),
+      window_did_miniaturize as extern "C" fn(_, _, _),
+    );
+    decl.add_method(
+      sel!(windowDidDeminiaturize:),
+      window_did_deminiaturize as extern "C" fn(_, _, _),
+    );
+    decl.add_method(
+      sel!(windowDidChangeOcclusionState:),
+      window_did_change_occlusion_state as extern "C" fn(_, _, _),
+    );
     decl.add_method(
       sel!(windowDidChangeBackingProperties:),
       window_did_change_backing_properties as extern "C" fn(_, _, _),
@@ -350,6 +395,7 @@ extern "C" fn window_did_resize(this: &Object, _: Sel, _: id) {
     if !state.is_checking_zoomed_in {
       state.emit_resize_event();
       state.emit_move_event();
+      state.emit_state_changed_event();
     }
   });
   trace!("Completed `windowDidResize:`");
@@ -364,6 +410,32 @@ extern "C" fn window_did_move(this: &Object, _: Sel, _: id) {
   trace!("Completed `windowDidMove:`");
 }
 
+extern "C" fn window_did_miniaturize(this: &Object, _: Sel, _: id) {
+  trace!("Triggered `windowDidMiniaturize:`");
+  with_state(this, |state| {
+    state.emit_state_changed_event();
+  });
+  trace!("Completed `windowDidMiniaturize:`");
+}
+
+extern "C" fn window_did_deminiaturize(this: &Object, _: Sel, _: id) {
+  trace!("Triggered `windowDidDeminiaturize:`");
+  with_state(this, |state| {
+    state.emit_state_changed_event();
+  });
+  trace!("Completed `windowDidDeminiaturize:`");
+}
+
+extern "C" fn window_did_change_occlusion_state(this: &Object, _: Sel, _: id) {
+  trace!("Triggered `windowDidChangeOcclusionState:`");
+  with_state(this, |state| {
+    let occlusion_state = unsafe { state.ns_window.occlusionState() };
+    let visible = occlusion_state.contains(NSWindowOcclusionState::Visible);
+    state.emit_event(WindowEvent::Occluded(!visible));
+  });
+  trace!("Completed `windowDidChangeOcclusionState:`");
+}
+
 extern "C" fn window_did_change_backing_properties(this: &Object, _: Sel, _: id) {
   trace!("Triggered `windowDidChangeBackingProperties:`");
   with_state(this, |state| {
@@ -378,6 +450,7 @@ extern "C" fn window_did_become_key(this: &Object, _: Sel, _: id) {
     // TODO: center the cursor if the window had mouse grab when it
     // lost focus
     state.emit_event(WindowEvent::Focused(true));
+    state.emit_state_changed_event();
   });
   trace!("Completed `windowDidBecomeKey:`");
 }
@@ -410,6 +483,7 @@ extern "C" fn window_did_resign_key(this: &Object, _: Sel, _: id) {
     }
 
     state.emit_event(WindowEvent::Focused(false));
+    state.emit_state_changed_event();
   });
   trace!("Completed `windowDidResignKey:`");
 }
@@ -587,6 +661,7 @@ extern "C" fn window_did_enter_fullscreen(this: &Object, _: Sel, _: id) {
     });
     state.emit_resize_event();
     state.emit_move_event();
+    state.emit_state_changed_event();
   });
   trace!("Completed `windowDidEnterFullscreen:`");
 }
@@ -609,6 +684,7 @@ extern "C" fn window_did_exit_fullscreen(this: &Object, _: Sel, _: id) {
     });
     state.emit_resize_event();
     state.emit_move_event();
+    state.emit_state_changed_event();
   });
   trace!("Completed `windowDidExitFullscreen:`");
 }
@@ -60,7 +60,7 @@ use crate::{
     window_state::{CursorFlags, WindowFlags, WindowState},
     wrap_device_id, WindowId, DEVICE_ID,
   },
-  window::{Fullscreen, Theme, WindowId as RootWindowId},
+  window::{Fullscreen, Theme, WindowId as RootWindowId, WindowStateFlags},
 };
 use runner::{EventLoopRunner, EventLoopRunnerShared};
 
@@ -894,6 +894,7 @@ unsafe fn gain_active_focus<T>(window: HWND, subclass_input: &SubclassInput<T>)
     window_id: RootWindowId(WindowId(window.0 as _)),
     event: Focused(true),
   });
+  send_state_changed_event(window, subclass_input);
 }
 
 unsafe fn lose_active_focus<T>(window: HWND, subclass_input: &SubclassInput<T>) {
@@ -909,6 +910,31 @@ unsafe fn lose_active_focus<T>(window: HWND, subclass_input: &SubclassInput<T>)
     window_id: RootWindowId(WindowId(window.0 as _)),
     event: Focused(false),
   });
+  send_state_changed_event(window, subclass_input);
+}
+
+/// Sends `WindowEvent::StateChanged` if the window state differs from the last reported one.
+unsafe fn send_state_changed_event<T>(window: HWND, subclass_input: &SubclassInput<T>) {
+  use crate::event::WindowEvent::StateChanged;
+
+  let state = {
+    let mut w = subclass_input.window_state.lock();
+    let mut state = WindowStateFlags::empty();
+    state.set(WindowStateFlags::MAXIMIZED, IsZoomed(window).as_bool());
+    state.set(WindowStateFlags::MINIMIZED, IsIconic(window).as_bool());
+    state.set(WindowStateFlags::FULLSCREEN, w.fullscreen.is_some());
+    state.set(WindowStateFlags::FOCUSED, w.has_active_focus());
+    if state == w.reported_state {
+      return;
+    }
+    w.reported_state = state;
+    state
+  };
+
+  subclass_input.send_event(Event::WindowEvent {
+    window_id: RootWindowId(WindowId(window.0 as _)),
+    event: StateChanged(state),
+  });
 }
 
 /// Any window whose callback is configured to this function will have its events propagated
@@ -1267,6 +1293,7 @@ unsafe fn public_window_callback_inner<T: 'static>(
       }
 
       subclass_input.send_event(event);
+      send_state_changed_event(window, subclass_input);
       result = ProcResult::Value(LRESULT(0));
     }
 
@@ -2237,6 +2264,10 @@ unsafe fn public_window_callback_inner<T: 'static>(
 
     _ => {
       if msg == *DESTROY_MSG_ID {
+        // The owner is enabled first, so that it is activated instead of another application.
+        if let Some(owner) = subclass_input.window_state.lock().modal_owner.take() {
+          let _ = EnableWindow(owner, true);
+        }
         let _ = DestroyWindow(window);
         result = ProcResult::Value(LRESULT(0));
       } else if msg == *SET_RETAIN_STATE_ON_SIZE_MSG_ID {
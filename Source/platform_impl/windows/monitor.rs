@@ -19,7 +19,7 @@ use std::{
 use super::util;
 use crate::{
   dpi::{PhysicalPosition, PhysicalSize},
-  monitor::{MonitorHandle as RootMonitorHandle, VideoMode as RootVideoMode},
+  monitor::{MonitorHandle as RootMonitorHandle, SubpixelLayout, VideoMode as RootVideoMode},
   platform_impl::platform::{
     dpi::{dpi_to_scale_factor, get_monitor_dpi},
     window::Window,
@@ -263,4 +263,65 @@ impl MonitorHandle {
 
     modes.into_iter()
   }
+
+  #[inline]
+  pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+    let monitor_info = get_monitor_info(self.hmonitor()).ok()?;
+    let device_name = PCWSTR::from_raw(monitor_info.szDevice.as_ptr());
+    let (width, height) = unsafe {
+      let hdc = CreateDCW(device_name, PCWSTR::null(), PCWSTR::null(), None);
+      if hdc.is_invalid() {
+        return None;
+      }
+      let size = (
+        GetDeviceCaps(Some(hdc), HORZSIZE),
+        GetDeviceCaps(Some(hdc), VERTSIZE),
+      );
+      let _ = DeleteDC(hdc);
+      size
+    };
+    // Virtual displays report a size of 0.
+    if width > 0 && height > 0 {
+      Some((width as u32, height as u32))
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+    let monitor_info = get_monitor_info(self.hmonitor()).ok()?;
+    let device_name = PCWSTR::from_raw(monitor_info.szDevice.as_ptr());
+    let mut mode: DEVMODEW = unsafe { mem::zeroed() };
+    mode.dmSize = mem::size_of_val(&mode) as u16;
+    let ok = unsafe {
+      EnumDisplaySettingsExW(
+        device_name,
+        ENUM_CURRENT_SETTINGS,
+        &mut mode,
+        ENUM_DISPLAY_SETTINGS_FLAGS(0),
+      )
+    };
+    // A frequency of 0 or 1 means the hardware default, which is unknown to us.
+    if ok.as_bool() && mode.dmDisplayFrequency > 1 {
+      Some(mode.dmDisplayFrequency * 1000)
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  pub fn manufacturer(&self) -> Option<String> {
+    None
+  }
+
+  #[inline]
+  pub fn connector_name(&self) -> Option<String> {
+    self.name()
+  }
+
+  #[inline]
+  pub fn subpixel_layout(&self) -> SubpixelLayout {
+    SubpixelLayout::Unknown
+  }
 }
@@ -51,8 +51,8 @@ use crate::{
     OsError, Parent, PlatformSpecificWindowBuilderAttributes, WindowId,
   },
   window::{
-    CursorIcon, Fullscreen, ProgressBarState, ProgressState, ResizeDirection, Theme,
-    UserAttentionType, WindowAttributes, WindowSizeConstraints, RGBA,
+    CursorIcon, DragActions, DragItem, Fullscreen, ProgressBarState, ProgressState,
+    ResizeDirection, Theme, UserAttentionType, WindowAttributes, WindowSizeConstraints, RGBA,
   },
 };
 
@@ -552,6 +552,16 @@ impl Window {
     self.handle_os_dragging(WPARAM(direction.to_win32() as _))
   }
 
+  #[inline]
+  pub fn start_drag(
+    &self,
+    _items: Vec<DragItem>,
+    _allowed_actions: DragActions,
+    _icon: Option<Icon>,
+  ) -> Result<(), ExternalError> {
+    Err(ExternalError::NotSupported(NotSupportedError::new()))
+  }
+
   #[inline]
   pub fn set_ignore_cursor_events(&self, ignore: bool) -> Result<(), ExternalError> {
     let window = self.window.0 .0 as isize;
@@ -1300,6 +1310,14 @@ unsafe fn init<T: 'static>(
     window_state
   };
 
+  // A modal window blocks the input to its owner, like a dialog box.
+  if attributes.modal {
+    if let Parent::OwnedBy(owner) = pl_attribs.parent {
+      let _ = EnableWindow(owner, false);
+      window_state.lock().modal_owner = Some(owner);
+    }
+  }
+
   let win = Window {
     window: real_window,
     window_state,
@@ -7,7 +7,9 @@ use crate::{
   icon::Icon,
   keyboard::ModifiersState,
   platform_impl::platform::{event_loop, minimal_ime::MinimalIme, util},
-  window::{CursorIcon, Fullscreen, Theme, WindowAttributes, WindowSizeConstraints, RGBA},
+  window::{
+    CursorIcon, Fullscreen, Theme, WindowAttributes, WindowSizeConstraints, WindowStateFlags, RGBA,
+  },
 };
 use parking_lot::MutexGuard;
 use std::io;
@@ -47,7 +49,13 @@ pub struct WindowState {
   pub is_active: bool,
   pub is_focused: bool,
 
+  // Last state sent through `WindowEvent::StateChanged`
+  pub reported_state: WindowStateFlags,
+
   pub background_color: Option<RGBA>,
+
+  // Owner disabled while the window is modal, enabled again before it is destroyed
+  pub modal_owner: Option<HWND>,
 }
 
 unsafe impl Send for WindowState {}
@@ -159,7 +167,11 @@ impl WindowState {
       is_active: false,
       is_focused: false,
 
+      reported_state: WindowStateFlags::empty(),
+
       background_color,
+
+      modal_owner: None,
     }
   }
 
@@ -3,12 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! The `Window` struct and associated types.
-use std::fmt;
+use std::{fmt, path::PathBuf};
 
 use crate::{
   dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Pixel, PixelUnit, Position, Size},
   error::{ExternalError, NotSupportedError, OsError},
-  event_loop::EventLoopWindowTarget,
+  event_loop::{EventLoopWindowTarget, InhibitCookie},
   monitor::{MonitorHandle, VideoMode},
   platform_impl,
 };
@@ -20,11 +20,11 @@ pub use crate::icon::{BadIcon, Icon};
 pub enum ProgressState {
   None,
   Normal,
-  /// **Treated as Normal in linux and macOS**
+  /// **Treated as Normal in macOS, hides the progress bar in linux**
   Indeterminate,
-  /// **Treated as Normal in linux**
+  /// **Treated as Normal in linux, the `LauncherEntry` protocol has no paused state**
   Paused,
-  /// **Treated as Normal in linux**
+  /// **Marks the launcher entry as urgent in linux**
   Error,
 }
 
@@ -33,7 +33,7 @@ pub struct ProgressBarState {
   pub state: Option<ProgressState>,
   /// The progress bar progress. This can be a value ranging from `0` to `100`
   pub progress: Option<u64>,
-  /// The `.desktop` filename with the Unity desktop window manager, for example `myapp.desktop` **Linux Only**
+  /// The `.desktop` filename of the application, for example `myapp.desktop`. Defaults to the application id followed by `.desktop` **Linux Only**
   pub desktop_filename: Option<String>,
 }
 
@@ -270,6 +270,39 @@ pub struct WindowAttributes {
   /// - **Windows:** alpha channel is ignored. Instead manually draw the window, for example using `softbuffer` crate, see <https://github.com/tauri-apps/tao/blob/dev/examples/transparent.rs>
   /// - **iOS / Android:** Unsupported.
   pub background_color: Option<RGBA>,
+
+  /// The opacity of the whole window including its content, from `0.0` (invisible) to `1.0`.
+  ///
+  /// The default is `1.0`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux(X11):** Requires a compositing window manager.
+  /// - **Linux(Wayland) / Windows / macOS / iOS / Android:** Unsupported.
+  pub opacity: f64,
+
+  /// The geometry to restore, which overrides `inner_size`, `position`, `maximized` and
+  /// `fullscreen` when the window is built.
+  ///
+  /// The default is `None`.
+  ///
+  /// See [`WindowBuilder::with_geometry`] for details.
+  pub geometry: Option<WindowGeometry>,
+
+  /// Where the window is placed, which overrides `position`.
+  ///
+  /// The default is `None`.
+  ///
+  /// See [`WindowBuilder::with_placement`] for details.
+  pub placement: Option<WindowPlacement>,
+
+  /// Whether the window blocks the input to the other windows of the application while it is
+  /// open.
+  ///
+  /// The default is `false`.
+  ///
+  /// See [`WindowBuilder::with_modal`] for details.
+  pub modal: bool,
 }
 
 impl Default for WindowAttributes {
@@ -297,6 +330,10 @@ impl Default for WindowAttributes {
       content_protection: false,
       visible_on_all_workspaces: false,
       background_color: None,
+      opacity: 1.0,
+      geometry: None,
+      placement: None,
+      modal: false,
     }
   }
 }
@@ -572,14 +609,123 @@ impl WindowBuilder {
     self
   }
 
+  /// Sets the opacity of the whole window including its content.
+  ///
+  /// See [`Window::set_opacity`] for details.
+  #[inline]
+  pub fn with_opacity(mut self, opacity: f64) -> WindowBuilder {
+    self.window.opacity = opacity;
+    self
+  }
+
+  /// Places the window relative to a monitor, its parent, the other windows or the cursor.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux(Wayland):** Unsupported, the compositor places the windows.
+  /// - **Windows / macOS:** Only [`WindowPlacement::CenterOnMonitor`] and
+  ///   [`WindowPlacement::AtCursor`] are supported, the decorations aren't accounted for.
+  /// - **iOS / Android:** Unsupported.
+  #[inline]
+  pub fn with_placement(mut self, placement: WindowPlacement) -> WindowBuilder {
+    self.window.placement = Some(placement);
+    self
+  }
+
+  /// Sets the window the window to be created belongs to. The window stays above its parent and
+  /// is destroyed with it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Sets the transient parent, see [`WindowBuilderExtUnix::with_transient_for`].
+  ///   The window also follows its parent across workspaces and is minimized with it.
+  /// - **Windows:** Sets the owner window, see [`WindowBuilderExtWindows::with_owner_window`].
+  /// - **macOS:** Adds the window as a child window, see [`WindowBuilderExtMacOS::with_parent_window`].
+  /// - **iOS / Android:** Unsupported.
+  ///
+  /// [`WindowBuilderExtUnix::with_transient_for`]: crate::platform::unix::WindowBuilderExtUnix::with_transient_for
+  /// [`WindowBuilderExtWindows::with_owner_window`]: crate::platform::windows::WindowBuilderExtWindows::with_owner_window
+  /// [`WindowBuilderExtMacOS::with_parent_window`]: crate::platform::macos::WindowBuilderExtMacOS::with_parent_window
+  #[inline]
+  pub fn with_parent(self, _parent: &Window) -> WindowBuilder {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    let builder = {
+      use crate::platform::unix::{WindowBuilderExtUnix, WindowExtUnix};
+      let mut builder = self.with_transient_for(_parent.gtk_window());
+      builder.platform_specific.destroy_with_parent = true;
+      builder
+    };
+    #[cfg(target_os = "windows")]
+    let builder = {
+      use crate::platform::windows::{WindowBuilderExtWindows, WindowExtWindows};
+      self.with_owner_window(_parent.hwnd())
+    };
+    #[cfg(target_os = "macos")]
+    let builder = {
+      use crate::platform::macos::{WindowBuilderExtMacOS, WindowExtMacOS};
+      self.with_parent_window(_parent.ns_window())
+    };
+    #[cfg(any(target_os = "ios", target_os = "android"))]
+    let builder = self;
+    builder
+  }
+
+  /// Sets whether the window blocks the input to its parent, see [`WindowBuilder::with_parent`],
+  /// or to all the other windows of the application if it has none.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows:** The parent is disabled until the window is dropped. Windows without a
+  ///   parent don't block any input.
+  /// - **macOS:** The window is kept above the other windows at the modal panel level, but its
+  ///   parent still receives input.
+  /// - **iOS / Android:** Unsupported.
+  #[inline]
+  pub fn with_modal(mut self, modal: bool) -> WindowBuilder {
+    self.window.modal = modal;
+    self
+  }
+
+  /// Restores the geometry returned by [`Window::geometry_snapshot`].
+  ///
+  /// The window is restored on the monitor it was on, or on the primary monitor if that monitor
+  /// is no longer connected. It is kept within the work area of that monitor and its size
+  /// is clamped to the [`WindowAttributes::inner_size_constraints`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux(Wayland):** The position is ignored.
+  /// - **iOS / Android:** Unsupported.
+  #[inline]
+  pub fn with_geometry(mut self, geometry: WindowGeometry) -> WindowBuilder {
+    self.window.geometry = Some(geometry);
+    self
+  }
+
   /// Builds the window.
   ///
   /// Possible causes of error include denied permission, incompatible system, and lack of memory.
   #[inline]
   pub fn build<T: 'static>(
-    self,
+    mut self,
     window_target: &EventLoopWindowTarget<T>,
   ) -> Result<Window, OsError> {
+    if let Some(geometry) = self.window.geometry.take() {
+      geometry.restore(&mut self.window, window_target);
+    }
+
+    if let Some(placement) = &self.window.placement {
+      if let Some(position) = placement.resolve(&self.window, window_target) {
+        self.window.position = Some(position);
+      }
+    }
+
     platform_impl::Window::new(&window_target.p, self.window, self.platform_specific).map(
       |window| {
         window.request_redraw();
@@ -750,6 +896,53 @@ impl Window {
     self.window.outer_size()
   }
 
+  /// Returns the geometry of the window, to be restored with [`WindowBuilder::with_geometry`]
+  /// when the application is started again.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** The position and size are the ones the window had when it was last neither
+  ///   maximized nor fullscreen.
+  /// - **Windows / macOS / iOS / Android:** The current position and size are recorded, even
+  ///   while the window is maximized or fullscreen.
+  /// - **Linux(Wayland) / Android:** The position is `None`.
+  #[inline]
+  pub fn geometry_snapshot(&self) -> WindowGeometry {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    ))]
+    let (position, size) = self.window.normal_geometry();
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd"
+    )))]
+    let (position, size) = (self.outer_position().ok(), self.inner_size());
+
+    // The decorations keep their size, so the outer size is derived from the restored one.
+    let (outer_size, inner_size) = (self.outer_size(), self.inner_size());
+    let outer_size = PhysicalSize::new(
+      size.width + outer_size.width.saturating_sub(inner_size.width),
+      size.height + outer_size.height.saturating_sub(inner_size.height),
+    );
+
+    WindowGeometry {
+      position,
+      size,
+      outer_size,
+      maximized: self.is_maximized(),
+      fullscreen: self.fullscreen().is_some(),
+      monitor: self.current_monitor().and_then(|monitor| monitor.name()),
+      scale_factor: self.scale_factor(),
+    }
+  }
+
   /// Sets a minimum dimension size for the window.
   ///
   /// ## Platform-specific
@@ -815,10 +1008,46 @@ impl Window {
     self.window.set_visible(visible)
   }
 
+  /// Prevents the screen from blanking and the screensaver from starting while the window is
+  /// visible, until the returned [`InhibitCookie`] is dropped.
+  ///
+  /// `reason` may be shown to the user, for example "Playing a video".
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Requires a session manager implementing `org.gnome.SessionManager` or
+  ///   `org.freedesktop.ScreenSaver`, or the inhibit portal when running in a sandbox.
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn inhibit_idle(&self, _reason: &str) -> Result<InhibitCookie, ExternalError> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    return self
+      .window
+      .inhibit_idle(_reason)
+      .map(|inner| InhibitCookie { _inner: inner });
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    )))]
+    return Err(ExternalError::NotSupported(NotSupportedError::new()));
+  }
+
   /// Bring the window to front and focus.
   ///
   /// ## Platform-specific
   ///
+  /// - **Linux:** Window managers with focus stealing prevention, and Wayland compositors, usually
+  ///   ignore this unless an activation token is passed along, see
+  ///   `WindowExtUnix::focus_with_activation_token`.
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_focus(&self) {
@@ -874,7 +1103,9 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **Linux:** Updates the window manager hints on X11 and the header bar buttons on Wayland.
+  ///   Whether the window manager honors the hints depends on the system.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_minimizable(&self, minimizable: bool) {
     self.window.set_minimizable(minimizable)
@@ -885,7 +1116,9 @@ impl Window {
   /// ## Platform-specific
   ///
   /// - **macOS:** Disables the "zoom" button in the window titlebar, which is also used to enter fullscreen mode.
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **Linux:** Updates the window manager hints on X11 and the header bar buttons on Wayland.
+  ///   A window that is not resizable is never maximizable.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_maximizable(&self, maximizable: bool) {
     self.window.set_maximizable(maximizable)
@@ -967,7 +1200,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn is_minimizable(&self) -> bool {
     self.window.is_minimizable()
@@ -977,7 +1210,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / iOS / Android:** Unsupported.
+  /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn is_maximizable(&self) -> bool {
     self.window.is_maximizable()
@@ -1099,7 +1332,7 @@ impl Window {
   ///
   /// ## Platform-specific
   ///
-  /// - **Linux / macOS**: Unlike windows, progress bar is app-wide and not specific to this window. Only supported desktop environments with `libunity` (e.g. GNOME).
+  /// - **Linux / macOS**: Unlike windows, progress bar is app-wide and not specific to this window. Only supported by docks implementing the Unity `LauncherEntry` D-Bus protocol (e.g. KDE Plasma, Dash-to-Dock, elementary).
   /// - **iOS / Android:** Unsupported.
   #[inline]
   pub fn set_progress_bar(&self, _progress: ProgressBarState) {
@@ -1192,6 +1425,89 @@ impl Window {
   pub fn set_background_color(&self, color: Option<RGBA>) {
     self.window.set_background_color(color)
   }
+
+  /// Sets the opacity of the whole window including its content, from `0.0` (invisible) to `1.0`,
+  /// for example to fade it in or out. Values outside of this range are clamped.
+  ///
+  /// Unlike [`Window::set_background_color`], this also applies to the content drawn by webviews or
+  /// GL surfaces.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux(X11):** Requires a compositing window manager.
+  /// - **Linux(Wayland) / Windows / macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn set_opacity(&self, _opacity: f64) {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    self.window.set_opacity(_opacity)
+  }
+
+  /// Returns the opacity last requested with [`Window::set_opacity`], which may not be the
+  /// effective opacity if the platform or the window manager doesn't support it.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Always returns `1.0`.
+  #[inline]
+  pub fn opacity(&self) -> f64 {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    return self.window.opacity();
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    )))]
+    return 1.0;
+  }
+
+  /// Captures the rendered content of the window, returning 32bpp RGBA pixels in the layout of
+  /// [`Icon::from_rgba`] and the size of the image.
+  ///
+  /// The image covers the client area of the window, or the whole window including the title
+  /// bar and borders if `include_decorations` is `true`.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** The window must be visible. Decorations drawn by the window manager are
+  ///   read from the screen, so other windows overlapping them are captured too. They can't be
+  ///   captured on Wayland, where only the decorations drawn by the application are included.
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn capture(
+    &self,
+    _include_decorations: bool,
+  ) -> Result<(Vec<u8>, PhysicalSize<u32>), ExternalError> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    return self.window.capture(_include_decorations);
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    )))]
+    return Err(ExternalError::NotSupported(NotSupportedError::new()));
+  }
 }
 
 /// Cursor functions.
@@ -1272,6 +1588,72 @@ impl Window {
     self.window.drag_resize_window(direction)
   }
 
+  /// Sets how the points of the window are classified when pressed, to let an undecorated window
+  /// draw its own title bar. `None` classifies every point as [`HitTestResult::Client`].
+  ///
+  /// Pressing a [`HitTestResult::Caption`] point and dragging moves the window, and
+  /// double-clicking it toggles maximized. Points in the resize border of an undecorated
+  /// resizable window resize it regardless of the hit test, see [`Window::set_resize_border`].
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn set_hit_test(&self, _hit_test: Option<HitTest>) {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    self.window.set_hit_test(_hit_test)
+  }
+
+  /// Sets the width of the border in which an undecorated resizable window is resized when
+  /// pressed. `0` disables it. Defaults to `5`.
+  ///
+  /// The width is multiplied by the scale factor of the window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Unsupported.
+  #[inline]
+  pub fn set_resize_border(&self, _width: u32) {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    self.window.set_resize_border(_width)
+  }
+
+  /// Starts dragging `items` out of the window with the left mouse button, until the button is
+  /// released.
+  ///
+  /// `icon` is shown under the cursor during the drag, centered on it. Once the drag ends,
+  /// [`WindowEvent::DragFinished`] is emitted with the action chosen by the drop target.
+  ///
+  /// There's no guarantee that this will work unless the left mouse button was pressed
+  /// immediately before this function is called.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Always returns an [`ExternalError::NotSupported`].
+  ///
+  /// [`WindowEvent::DragFinished`]: crate::event::WindowEvent::DragFinished
+  #[inline]
+  pub fn start_drag(
+    &self,
+    items: Vec<DragItem>,
+    allowed_actions: DragActions,
+    icon: Option<Icon>,
+  ) -> Result<(), ExternalError> {
+    self.window.start_drag(items, allowed_actions, icon)
+  }
+
   /// Modifies whether the window catches cursor events.
   ///
   /// If `true`, the events are passed through the window such that any other window behind it receives them.
@@ -1285,6 +1667,64 @@ impl Window {
     self.window.set_ignore_cursor_events(ignore)
   }
 
+  /// Sets the area of the window that receives cursor events, the events outside of it are passed
+  /// to the window behind. `None` restores the whole window.
+  ///
+  /// This replaces the input region set by [`Window::set_ignore_cursor_events`], and the other way
+  /// around.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS / iOS / Android:** Always returns an [`ExternalError::NotSupported`].
+  #[inline]
+  pub fn set_input_region(&self, _region: Option<WindowRegion>) -> Result<(), ExternalError> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    return self.window.set_input_region(_region);
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    )))]
+    return Err(ExternalError::NotSupported(NotSupportedError::new()));
+  }
+
+  /// Sets the shape of the window, the window is neither drawn nor receives cursor events
+  /// outside of it. `None` restores a rectangular window.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux(Wayland):** Windows can't be shaped, only the input region is limited to the
+  ///   shape, keeping the region set with [`Window::set_input_region`]. Draw the window
+  ///   transparent outside of the shape instead.
+  /// - **Windows / macOS / iOS / Android:** Always returns an [`ExternalError::NotSupported`].
+  #[inline]
+  pub fn set_shape(&self, _shape: Option<WindowRegion>) -> Result<(), ExternalError> {
+    #[cfg(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    ))]
+    return self.window.set_shape(_shape);
+    #[cfg(not(any(
+      target_os = "linux",
+      target_os = "dragonfly",
+      target_os = "freebsd",
+      target_os = "netbsd",
+      target_os = "openbsd",
+    )))]
+    return Err(ExternalError::NotSupported(NotSupportedError::new()));
+  }
+
   /// Returns the current cursor position
   ///
   /// ## Platform-specific
@@ -1485,6 +1925,247 @@ pub enum UserAttentionType {
   Informational,
 }
 
+bitflags! {
+  /// Represents the state of a window as reported by the window manager.
+  ///
+  /// Each flag is set if the window is currently in that state.
+  /// See [`WindowEvent::StateChanged`](crate::event::WindowEvent::StateChanged).
+  #[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+  pub struct WindowStateFlags: u32 {
+    /// The window is maximized.
+    const MAXIMIZED = 1 << 0;
+    /// The window is minimized (iconified).
+    const MINIMIZED = 1 << 1;
+    /// The window is fullscreen.
+    const FULLSCREEN = 1 << 2;
+    /// The window has keyboard focus.
+    const FOCUSED = 1 << 3;
+    /// The top edge of the window is tiled against a screen edge or another window.
+    const TILED_TOP = 1 << 4;
+    /// The right edge of the window is tiled against a screen edge or another window.
+    const TILED_RIGHT = 1 << 5;
+    /// The bottom edge of the window is tiled against a screen edge or another window.
+    const TILED_BOTTOM = 1 << 6;
+    /// The left edge of the window is tiled against a screen edge or another window.
+    const TILED_LEFT = 1 << 7;
+    /// The window is tiled on at least one edge.
+    const TILED = Self::TILED_TOP.bits()
+      | Self::TILED_RIGHT.bits()
+      | Self::TILED_BOTTOM.bits()
+      | Self::TILED_LEFT.bits();
+  }
+}
+
+/// A piece of content offered by a drag started with [`Window::start_drag`].
+///
+/// The drop target picks the item it understands best.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DragItem {
+  /// Local files, offered as a `text/uri-list`.
+  Files(Vec<PathBuf>),
+  /// UTF-8 text.
+  Text(String),
+  /// Arbitrary data of the given MIME type.
+  Data { mime_type: String, data: Vec<u8> },
+}
+
+bitflags! {
+  /// The actions a drop target may perform with the content of a drag started with
+  /// [`Window::start_drag`].
+  #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+  pub struct DragActions: u32 {
+    /// The content may be copied.
+    const COPY = 1 << 0;
+    /// The content may be moved, the application is expected to delete its copy afterwards.
+    const MOVE = 1 << 1;
+    /// The drop target may create a link to the content.
+    const LINK = 1 << 2;
+  }
+}
+
+/// The action performed by the drop target of a drag started with [`Window::start_drag`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DragAction {
+  Copy,
+  Move,
+  Link,
+}
+
+/// How a drag started with [`Window::start_drag`] ended.
+///
+/// See [`WindowEvent::DragFinished`](crate::event::WindowEvent::DragFinished).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DragResult {
+  /// The content was dropped and the drop target performed the given action.
+  Dropped(DragAction),
+  /// The drag was cancelled by the user or no drop target accepted the content.
+  Cancelled,
+}
+
+/// Where a window is placed when it is created, see [`WindowBuilder::with_placement`].
+///
+/// The window is placed with its decorations, in the work area of the monitor.
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum WindowPlacement {
+  /// Centered on the given monitor, or on the primary monitor if `None`.
+  CenterOnMonitor(Option<MonitorHandle>),
+  /// Centered on the parent window, see [`WindowBuilder::with_parent`].
+  CenterOnParent,
+  /// Offset down and to the right from the most recently focused window of the application,
+  /// starting again from the top-left corner of the monitor when it would overflow it.
+  Cascade,
+  /// Centered on the mouse cursor.
+  AtCursor,
+}
+
+impl WindowPlacement {
+  /// Resolves the placements that only depend on the monitors and the cursor to the outer
+  /// position of the window. The platforms refine them where they can, e.g. with the decorations.
+  fn resolve<T: 'static>(
+    &self,
+    attributes: &WindowAttributes,
+    window_target: &EventLoopWindowTarget<T>,
+  ) -> Option<Position> {
+    let (monitor, center) = match self {
+      WindowPlacement::CenterOnMonitor(monitor) => {
+        let monitor = monitor
+          .clone()
+          .or_else(|| window_target.primary_monitor())
+          .or_else(|| window_target.available_monitors().next())?;
+        let (position, size) = monitor.work_area();
+        let center = PhysicalPosition::new(
+          position.x + size.width as i32 / 2,
+          position.y + size.height as i32 / 2,
+        );
+        (monitor, center)
+      }
+      WindowPlacement::AtCursor => {
+        let cursor: PhysicalPosition<i32> = window_target.cursor_position().ok()?.cast();
+        let monitor = window_target.available_monitors().find(|monitor| {
+          let (position, size) = (monitor.position(), monitor.size());
+          (position.x..position.x + size.width as i32).contains(&cursor.x)
+            && (position.y..position.y + size.height as i32).contains(&cursor.y)
+        })?;
+        (monitor, cursor)
+      }
+      WindowPlacement::CenterOnParent | WindowPlacement::Cascade => return None,
+    };
+
+    let size: PhysicalSize<i32> = attributes
+      .inner_size
+      .unwrap_or_else(|| PhysicalSize::new(800, 600).into())
+      .to_physical(monitor.scale_factor());
+
+    // Keep the window on the work area of the monitor.
+    let (area_position, area_size) = monitor.work_area();
+    let max_x = area_position.x + area_size.width as i32 - size.width;
+    let max_y = area_position.y + area_size.height as i32 - size.height;
+    Some(
+      PhysicalPosition::new(
+        (center.x - size.width / 2).clamp(area_position.x, max_x.max(area_position.x)),
+        (center.y - size.height / 2).clamp(area_position.y, max_y.max(area_position.y)),
+      )
+      .into(),
+    )
+  }
+}
+
+/// The geometry of a window, returned by [`Window::geometry_snapshot`] and restored with
+/// [`WindowBuilder::with_geometry`].
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WindowGeometry {
+  /// The position of the top-left hand corner of the window, see [`Window::outer_position`].
+  pub position: Option<PhysicalPosition<i32>>,
+  /// The size of the window's client area, see [`Window::inner_size`].
+  pub size: PhysicalSize<u32>,
+  /// The size of the window including the decorations, see [`Window::outer_size`].
+  pub outer_size: PhysicalSize<u32>,
+  /// Whether the window was maximized, see [`Window::is_maximized`].
+  pub maximized: bool,
+  /// Whether the window was fullscreen, it is restored as borderless fullscreen.
+  pub fullscreen: bool,
+  /// The name of the monitor the window was on.
+  pub monitor: Option<String>,
+  /// The scale factor of the window, which `position` and `size` are relative to.
+  pub scale_factor: f64,
+}
+
+impl WindowGeometry {
+  fn restore<T: 'static>(
+    &self,
+    attributes: &mut WindowAttributes,
+    window_target: &EventLoopWindowTarget<T>,
+  ) {
+    let monitor = self
+      .monitor
+      .as_ref()
+      .and_then(|name| {
+        window_target
+          .available_monitors()
+          .find(|monitor| monitor.name().as_ref() == Some(name))
+      })
+      .or_else(|| window_target.primary_monitor())
+      .or_else(|| window_target.available_monitors().next());
+
+    let Some(monitor) = monitor else {
+      attributes.position = self.position.map(Into::into);
+      attributes.inner_size = Some(self.size.into());
+      attributes.maximized = self.maximized;
+      return;
+    };
+
+    // The size is kept in logical pixels, so that it doesn't change on a monitor with another
+    // scale factor.
+    let scale_factor = monitor.scale_factor();
+    let size: LogicalSize<f64> = self.size.to_logical(self.scale_factor);
+    let size: PhysicalSize<u32> = attributes
+      .inner_size_constraints
+      .clamp(size.into(), scale_factor)
+      .to_physical(scale_factor);
+
+    // The saved position is the outer one, so the decorations are kept on the work area too.
+    let decorations: LogicalSize<f64> = PhysicalSize::new(
+      self.outer_size.width.saturating_sub(self.size.width),
+      self.outer_size.height.saturating_sub(self.size.height),
+    )
+    .to_logical(self.scale_factor);
+    let decorations: PhysicalSize<u32> = decorations.to_physical(scale_factor);
+
+    let (area_position, area_size) = monitor.work_area();
+    let size = PhysicalSize::new(
+      size
+        .width
+        .min(area_size.width.saturating_sub(decorations.width)),
+      size
+        .height
+        .min(area_size.height.saturating_sub(decorations.height)),
+    );
+    attributes.inner_size = Some(size.into());
+
+    attributes.position = self.position.map(|position| {
+      let max_x =
+        area_position.x + area_size.width as i32 - (size.width + decorations.width) as i32;
+      let max_y =
+        area_position.y + area_size.height as i32 - (size.height + decorations.height) as i32;
+      PhysicalPosition::new(
+        position.x.clamp(area_position.x, max_x.max(area_position.x)),
+        position.y.clamp(area_position.y, max_y.max(area_position.y)),
+      )
+      .into()
+    });
+
+    attributes.maximized = self.maximized;
+    if self.fullscreen {
+      attributes.fullscreen = Some(Fullscreen::Borderless(Some(monitor)));
+    }
+  }
+}
+
 /// Window size constraints
 #[derive(Clone, Copy, PartialEq, Debug, Default)]
 pub struct WindowSizeConstraints {
@@ -1504,6 +2185,35 @@ pub struct WindowSizeConstraints {
   ///
   /// The default is `None`.
   pub max_height: Option<PixelUnit>,
+  /// The minimum aspect ratio (width / height) a window can have, If this is `None`, the window
+  /// can be arbitrarily tall.
+  ///
+  /// The default is `None`.
+  pub min_aspect: Option<f64>,
+  /// The maximum aspect ratio (width / height) a window can have, If this is `None`, the window
+  /// can be arbitrarily wide.
+  ///
+  /// The default is `None`.
+  pub max_aspect: Option<f64>,
+  /// The width the width increments are added to, If this is `None`, the minimum width is used.
+  ///
+  /// The default is `None`.
+  pub base_width: Option<PixelUnit>,
+  /// The height the height increments are added to, If this is `None`, the minimum height is
+  /// used.
+  ///
+  /// The default is `None`.
+  pub base_height: Option<PixelUnit>,
+  /// The step the width of a window changes by when resized, for example the width of a
+  /// character cell. If this is `None`, the width can change by any amount.
+  ///
+  /// The default is `None`.
+  pub width_increment: Option<PixelUnit>,
+  /// The step the height of a window changes by when resized, for example the height of a
+  /// character cell. If this is `None`, the height can change by any amount.
+  ///
+  /// The default is `None`.
+  pub height_increment: Option<PixelUnit>,
 }
 
 impl WindowSizeConstraints {
@@ -1518,9 +2228,51 @@ impl WindowSizeConstraints {
       min_height,
       max_width,
       max_height,
+      ..Default::default()
     }
   }
 
+  /// Sets the minimum and maximum aspect ratios (width / height).
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS:** Only applied to the sizes set by the application, not while the user
+  ///   resizes the window.
+  /// - **iOS / Android:** Unsupported.
+  pub fn with_aspect_ratio(mut self, min_aspect: Option<f64>, max_aspect: Option<f64>) -> Self {
+    self.min_aspect = min_aspect;
+    self.max_aspect = max_aspect;
+    self
+  }
+
+  /// Sets the size the increments are added to.
+  pub fn with_base_size(
+    mut self,
+    base_width: Option<PixelUnit>,
+    base_height: Option<PixelUnit>,
+  ) -> Self {
+    self.base_width = base_width;
+    self.base_height = base_height;
+    self
+  }
+
+  /// Sets the steps the size of the window changes by when resized.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Windows / macOS:** Only applied to the sizes set by the application, not while the user
+  ///   resizes the window.
+  /// - **iOS / Android:** Unsupported.
+  pub fn with_increments(
+    mut self,
+    width_increment: Option<PixelUnit>,
+    height_increment: Option<PixelUnit>,
+  ) -> Self {
+    self.width_increment = width_increment;
+    self.height_increment = height_increment;
+    self
+  }
+
   /// Returns true if `min_width` or `min_height` is set.
   pub fn has_min(&self) -> bool {
     self.min_width.is_some() || self.min_height.is_some()
@@ -1529,6 +2281,18 @@ impl WindowSizeConstraints {
   pub fn has_max(&self) -> bool {
     self.max_width.is_some() || self.max_height.is_some()
   }
+  /// Returns true if `min_aspect` or `max_aspect` is set.
+  pub fn has_aspect(&self) -> bool {
+    self.min_aspect.is_some() || self.max_aspect.is_some()
+  }
+  /// Returns true if `base_width` or `base_height` is set.
+  pub fn has_base(&self) -> bool {
+    self.base_width.is_some() || self.base_height.is_some()
+  }
+  /// Returns true if `width_increment` or `height_increment` is set.
+  pub fn has_increments(&self) -> bool {
+    self.width_increment.is_some() || self.height_increment.is_some()
+  }
 
   /// Returns a physical size that represents the minimum constraints set and fallbacks to [`PixelUnit::MIN`] for unset values
   pub fn min_size_physical<T: Pixel>(&self, scale_factor: f64) -> PhysicalSize<T> {
@@ -1594,11 +2358,82 @@ impl WindowSizeConstraints {
     )
   }
 
+  /// Returns a physical size that represents the base size set and fallbacks to the minimum size
+  /// for unset values
+  pub fn base_size_physical<T: Pixel>(&self, scale_factor: f64) -> PhysicalSize<T> {
+    let min_size: PhysicalSize<T> = self.min_size_physical(scale_factor);
+    PhysicalSize::new(
+      self
+        .base_width
+        .map_or(min_size.width, |width| width.to_physical(scale_factor).0),
+      self
+        .base_height
+        .map_or(min_size.height, |height| height.to_physical(scale_factor).0),
+    )
+  }
+
   /// Clamps the desired size based on the constraints set
+  ///
+  /// The size is rounded down to the increments, then the aspect ratio is corrected by shrinking
+  /// the size if the minimum size allows it, or growing it otherwise.
   pub fn clamp(&self, desired_size: Size, scale_factor: f64) -> Size {
     let min_size: PhysicalSize<f64> = self.min_size_physical(scale_factor);
     let max_size: PhysicalSize<f64> = self.max_size_physical(scale_factor);
-    Size::clamp(desired_size, min_size.into(), max_size.into(), scale_factor)
+    let size = Size::clamp(desired_size, min_size.into(), max_size.into(), scale_factor);
+    if !self.has_increments() && !self.has_aspect() {
+      return size;
+    }
+
+    let base_size: PhysicalSize<f64> = self.base_size_physical(scale_factor);
+    let increment = |increment: Option<PixelUnit>| {
+      increment
+        .map(|increment| increment.to_physical::<f64>(scale_factor).0)
+        .filter(|increment| *increment >= 1.0)
+        .unwrap_or(1.0)
+    };
+    let (width_increment, height_increment) = (
+      increment(self.width_increment),
+      increment(self.height_increment),
+    );
+    // Rounds `delta` down to a multiple of the increment.
+    let floor = |delta: f64, increment: f64| (delta / increment).floor() * increment;
+
+    let PhysicalSize {
+      mut width,
+      mut height,
+    } = size.to_physical::<f64>(scale_factor);
+    width = base_size.width + floor(width - base_size.width, width_increment).max(0.0);
+    height = base_size.height + floor(height - base_size.height, height_increment).max(0.0);
+
+    // Follows `gdk_window_constrain_size`.
+    if let Some(min_aspect) = self.min_aspect.filter(|aspect| *aspect > 0.0) {
+      if min_aspect * height > width {
+        let delta = floor(height - width / min_aspect, height_increment);
+        if height - delta >= min_size.height {
+          height -= delta;
+        } else {
+          let delta = floor(height * min_aspect - width, width_increment);
+          if width + delta <= max_size.width {
+            width += delta;
+          }
+        }
+      }
+    }
+    if let Some(max_aspect) = self.max_aspect.filter(|aspect| *aspect > 0.0) {
+      if max_aspect * height < width {
+        let delta = floor(width - height * max_aspect, width_increment);
+        if width - delta >= min_size.width {
+          width -= delta;
+        } else {
+          let delta = floor(width / max_aspect - height, height_increment);
+          if height + delta <= max_size.height {
+            height += delta;
+          }
+        }
+      }
+    }
+
+    PhysicalSize::new(width, height).into()
   }
 }
 
@@ -1615,6 +2450,153 @@ pub enum ResizeDirection {
   West,
 }
 
+/// Describes what a point of the window is used for, see [`Window::set_hit_test`].
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum HitTestResult {
+  /// The point belongs to the content of the window.
+  #[default]
+  Client,
+  /// The point belongs to a title bar drawn by the application.
+  Caption,
+  /// Pressing the point resizes the window in the given direction.
+  Resize(ResizeDirection),
+  /// The point belongs to a minimize button drawn by the application.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Handled as [`HitTestResult::Client`].
+  MinimizeButton,
+  /// The point belongs to a maximize button drawn by the application.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Handled as [`HitTestResult::Client`].
+  MaximizeButton,
+  /// The point belongs to a close button drawn by the application.
+  ///
+  /// ## Platform-specific
+  ///
+  /// - **Linux:** Handled as [`HitTestResult::Client`].
+  CloseButton,
+}
+
+/// A rectangle of the window classified as `result`, see [`HitTest::Regions`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HitTestRegion {
+  /// The top-left corner of the region, relative to the top-left corner of the window.
+  pub position: PhysicalPosition<i32>,
+  pub size: PhysicalSize<u32>,
+  pub result: HitTestResult,
+}
+
+impl HitTestRegion {
+  pub fn new(position: PhysicalPosition<i32>, size: PhysicalSize<u32>, result: HitTestResult) -> Self {
+    Self {
+      position,
+      size,
+      result,
+    }
+  }
+
+  /// Returns whether the region contains `point`, relative to the top-left corner of the window.
+  pub fn contains(&self, point: PhysicalPosition<f64>) -> bool {
+    let (x, y) = (self.position.x as f64, self.position.y as f64);
+    point.x >= x
+      && point.y >= y
+      && point.x < x + self.size.width as f64
+      && point.y < y + self.size.height as f64
+  }
+}
+
+/// Classifies the points of the window, see [`Window::set_hit_test`].
+pub enum HitTest {
+  /// Tests the regions in order, points outside of every region are [`HitTestResult::Client`].
+  Regions(Vec<HitTestRegion>),
+  /// Calls the function with the point, relative to the top-left corner of the window.
+  Callback(Box<dyn Fn(PhysicalPosition<f64>) -> HitTestResult + Send + Sync>),
+}
+
+impl HitTest {
+  /// Classifies `point`, relative to the top-left corner of the window.
+  pub fn test(&self, point: PhysicalPosition<f64>) -> HitTestResult {
+    match self {
+      HitTest::Regions(regions) => regions
+        .iter()
+        .find(|region| region.contains(point))
+        .map_or(HitTestResult::Client, |region| region.result),
+      HitTest::Callback(f) => f(point),
+    }
+  }
+}
+
+impl fmt::Debug for HitTest {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      HitTest::Regions(regions) => f.debug_tuple("Regions").field(regions).finish(),
+      HitTest::Callback(_) => f.debug_tuple("Callback").finish_non_exhaustive(),
+    }
+  }
+}
+
+/// An 8-bit alpha mask of the window, see [`WindowRegion::Mask`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlphaMask {
+  pub(crate) alpha: Vec<u8>,
+  pub(crate) width: u32,
+  pub(crate) height: u32,
+}
+
+impl AlphaMask {
+  /// Creates a mask from one alpha value per pixel, row by row.
+  ///
+  /// Returns `None` if the length of `alpha` isn't `width * height`.
+  pub fn from_alpha(alpha: Vec<u8>, width: u32, height: u32) -> Option<Self> {
+    (alpha.len() == width as usize * height as usize).then_some(Self {
+      alpha,
+      width,
+      height,
+    })
+  }
+
+  /// Creates a mask from the alpha channel of 32bpp RGBA data.
+  ///
+  /// Returns `None` if the length of `rgba` isn't `width * height * 4`.
+  pub fn from_rgba(rgba: &[u8], width: u32, height: u32) -> Option<Self> {
+    if rgba.len() != width as usize * height as usize * 4 {
+      return None;
+    }
+
+    let alpha = rgba.chunks_exact(4).map(|pixel| pixel[3]).collect();
+    Some(Self {
+      alpha,
+      width,
+      height,
+    })
+  }
+
+  /// Returns the width of the mask in physical pixels.
+  pub fn width(&self) -> u32 {
+    self.width
+  }
+
+  /// Returns the height of the mask in physical pixels.
+  pub fn height(&self) -> u32 {
+    self.height
+  }
+}
+
+/// An area of the window, see [`Window::set_input_region`] and [`Window::set_shape`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowRegion {
+  /// The union of the rectangles, each given by its top-left corner relative to the top-left
+  /// corner of the window and its size.
+  Rectangles(Vec<(PhysicalPosition<i32>, PhysicalSize<u32>)>),
+  /// The pixels of the mask that are more than half opaque, one pixel of the mask per physical
+  /// pixel of the window from its top-left corner.
+  Mask(AlphaMask),
+}
+
 pub(crate) fn hit_test(
   (left, top, right, bottom): (i32, i32, i32, i32),
   cx: i32,